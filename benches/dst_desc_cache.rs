@@ -0,0 +1,99 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+//! Benchmark for the destination descriptor cache in [`MappingTree::accept`].
+//!
+//! Compares writing SRG with `accept`, which remaps each distinct descriptor once,
+//! against remapping the descriptor of every member separately.
+//! Run with `cargo bench --bench dst_desc_cache`.
+
+use std::fmt::Write;
+use std::time::Instant;
+use jvm_obfuscation_mappings::MappedElementKind;
+use jvm_obfuscation_mappings::descriptor::remap_descriptor;
+use jvm_obfuscation_mappings::format::srg::SrgWriter;
+use jvm_obfuscation_mappings::tree::MappingTree;
+use jvm_obfuscation_mappings::visitor::MappingVisitor;
+
+const CLASSES: usize = 2000;
+const METHODS_PER_CLASS: usize = 20;
+const SHARED_DESCS: [&str; 4] = ["(La;)V", "(La;Lb;)Lc;", "([Lb;I)La;", "()Lc;"];
+
+fn synthetic_tree() -> anyhow::Result<MappingTree> {
+    let mut tree = MappingTree::new();
+    tree.visit_namespaces("official", &["named"])?;
+
+    for class in ["a", "b", "c"] {
+        tree.visit_class(class)?;
+        tree.visit_dst_name(MappedElementKind::Class, 0, &format!("com/example/{}", class.to_uppercase()))?;
+        tree.visit_element_content(MappedElementKind::Class)?;
+    }
+
+    for class in 0..CLASSES {
+        tree.visit_class(&format!("class_{}", class))?;
+        tree.visit_dst_name(MappedElementKind::Class, 0, &format!("com/example/Class{}", class))?;
+        tree.visit_element_content(MappedElementKind::Class)?;
+
+        for method in 0..METHODS_PER_CLASS {
+            tree.visit_method(&format!("method_{}", method), Some(SHARED_DESCS[method % SHARED_DESCS.len()]))?;
+            tree.visit_dst_name(MappedElementKind::Method, 0, &format!("method{}", method))?;
+            tree.visit_element_content(MappedElementKind::Method)?;
+        }
+    }
+
+    tree.visit_end()?;
+    Ok(tree)
+}
+
+/// Writes the SRG output of a tree by remapping every descriptor separately.
+fn write_uncached(tree: &MappingTree) -> anyhow::Result<String> {
+    let mut output = String::new();
+
+    for class in tree.classes() {
+        let class_dst_name = class.dst_name(0).unwrap_or(class.src_name());
+        writeln!(output, "CL: {} {}", class.src_name(), class_dst_name)?;
+
+        for method in class.methods() {
+            let src_desc = method.src_desc().unwrap();
+            let dst_desc = remap_descriptor(src_desc, |name| {
+                tree.class(name).and_then(|class| class.dst_name(0)).map(str::to_owned)
+            });
+            writeln!(
+                output, "MD: {}/{} {} {}/{} {}",
+                class.src_name(), method.src_name(), src_desc, class_dst_name, method.dst_name(0).unwrap(), dst_desc
+            )?;
+        }
+    }
+
+    Ok(output)
+}
+
+fn main() -> anyhow::Result<()> {
+    let tree = synthetic_tree()?;
+
+    let start = Instant::now();
+    let uncached = write_uncached(&tree)?;
+    let uncached_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut cached = String::new();
+    tree.accept(&mut SrgWriter::new(&mut cached))?;
+    let cached_time = start.elapsed();
+
+    assert_eq!(cached, uncached);
+    println!("uncached: {:?}, cached accept: {:?}", uncached_time, cached_time);
+    Ok(())
+}
@@ -0,0 +1,218 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use crate::MappedElementKind;
+use crate::adapter::{TwoPassAdapter, TwoPassVisitor};
+use crate::visitor::{MappingVisitor, VisitResult};
+
+/// An anonymous class (a class whose innermost name segment is a number, such as `Outer$1`)
+/// passed to the identity function of an [`AnonymousClassNormalizer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnonymousClass<'a> {
+    outer_name: &'a str,
+    index: &'a str,
+    first_method_desc: Option<&'a str>,
+}
+
+impl<'a> AnonymousClass<'a> {
+    /// Parses an anonymous class from its internal name.
+    ///
+    /// Returns `None` if the name isn't the name of an anonymous class.
+    fn parse(name: &'a str) -> Option<AnonymousClass<'a>> {
+        let (outer_name, index) = name.rsplit_once('$')?;
+
+        if outer_name.is_empty() || index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(AnonymousClass { outer_name, index, first_method_desc: None })
+    }
+
+    /// Returns the internal name of the enclosing class (`Outer` for `Outer$1`).
+    pub fn outer_name(&self) -> &'a str {
+        self.outer_name
+    }
+
+    /// Returns the numeric suffix of the class name (`1` for `Outer$1`).
+    pub fn index(&self) -> &'a str {
+        self.index
+    }
+
+    /// Returns the source descriptor of the first method visited in the class, if any.
+    pub fn first_method_desc(&self) -> Option<&'a str> {
+        self.first_method_desc
+    }
+}
+
+/// The identity function used by [`AnonymousClassNormalizer::new`], which leaves all names unchanged.
+pub fn keep_anonymous_class_index(_class: &AnonymousClass) -> Option<String> {
+    None
+}
+
+/// An identity function for [`AnonymousClassNormalizer`] that derives the tag
+/// from a hash of the class's first method descriptor.
+///
+/// Classes without methods keep their numeric names.
+pub fn first_method_desc_tag(class: &AnonymousClass) -> Option<String> {
+    class.first_method_desc().map(|desc| format!("anon_{:016x}", fnv1a(desc)))
+}
+
+/// Computes the 64-bit FNV-1a hash of a string, which is stable across runs and platforms.
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// A visitor that rewrites the numeric suffixes of anonymous classes (`Outer$1`) to stable tags.
+///
+/// The tag is computed by a pluggable identity function that receives an [`AnonymousClass`]
+/// and returns the new innermost name segment, or `None` to keep the numeric one.
+/// The class is then renamed to `Outer$<tag>` in the source namespace and in every destination namespace
+/// where the class also has an anonymous name. If the renamed class would clash with another class,
+/// the original name is kept.
+///
+/// Since the identity may depend on the class contents, this visitor needs an additional visitation pass
+/// to collect them before anything is passed on. Class references in descriptors are not rewritten.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::AnonymousClassNormalizer;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut tree = MappingTree::new();
+/// tree.visit_namespaces("official", &["named"])?;
+/// for name in ["Foo$1", "Foo$Bar"] {
+///     tree.visit_class(name)?;
+///     tree.visit_element_content(MappedElementKind::Class)?;
+/// }
+/// tree.visit_end()?;
+///
+/// let mut seen = Vec::new();
+/// let mut normalizer = AnonymousClassNormalizer::with_identity(MappingTree::new(), |class| {
+///     seen.push(format!("{}${}", class.outer_name(), class.index()));
+///     Some("Stable".to_owned())
+/// });
+/// tree.accept(&mut normalizer)?;
+/// let normalized = normalizer.into_inner();
+///
+/// assert_eq!(seen, vec!["Foo$1"]);
+/// assert!(normalized.class("Foo$Stable").is_some());
+/// assert!(normalized.class("Foo$Bar").is_some());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub type AnonymousClassNormalizer<V, F = fn(&AnonymousClass) -> Option<String>> = TwoPassVisitor<AnonymousClassAdapter<F>, V>;
+
+/// The collection and rewrite logic of an [`AnonymousClassNormalizer`].
+pub struct AnonymousClassAdapter<F> {
+    identity: F,
+    class_names: HashSet<String>,
+    first_method_descs: HashMap<String, Option<String>>,
+    current_class: Option<String>,
+    renamed_classes: HashSet<String>,
+    current_tag: Option<String>,
+}
+
+impl<V: MappingVisitor> AnonymousClassNormalizer<V> {
+    /// Creates a new normalizer that leaves all names unchanged.
+    pub fn new(next: V) -> Self {
+        AnonymousClassNormalizer::with_identity(next, keep_anonymous_class_index)
+    }
+}
+
+impl<V: MappingVisitor, F: FnMut(&AnonymousClass) -> Option<String>> AnonymousClassNormalizer<V, F> {
+    /// Creates a new normalizer that computes tags using the identity function `identity`.
+    pub fn with_identity(next: V, identity: F) -> Self {
+        TwoPassVisitor::with_adapter(next, AnonymousClassAdapter {
+            identity,
+            class_names: HashSet::new(),
+            first_method_descs: HashMap::new(),
+            current_class: None,
+            renamed_classes: HashSet::new(),
+            current_tag: None,
+        })
+    }
+}
+
+impl<F: FnMut(&AnonymousClass) -> Option<String>> TwoPassAdapter for AnonymousClassAdapter<F> {
+    fn reset(&mut self) {
+        self.class_names.clear();
+        self.first_method_descs.clear();
+        self.current_class = None;
+        self.renamed_classes.clear();
+        self.current_tag = None;
+    }
+
+    fn collect_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_names.insert(src_name.to_owned());
+
+        if AnonymousClass::parse(src_name).is_some() {
+            self.first_method_descs.entry(src_name.to_owned()).or_insert(None);
+            self.current_class = Some(src_name.to_owned());
+            return Ok(true);
+        }
+
+        self.current_class = None;
+        Ok(false)
+    }
+
+    fn collect_method(&mut self, _src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if let (Some(class), Some(src_desc)) = (&self.current_class, src_desc) {
+            if let Some(first_desc @ None) = self.first_method_descs.get_mut(class) {
+                *first_desc = Some(src_desc.to_owned());
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn visit_class<V: MappingVisitor>(&mut self, next: &mut V, src_name: &str) -> VisitResult<bool> {
+        self.current_tag = None;
+
+        if let Some(mut class) = AnonymousClass::parse(src_name) {
+            class.first_method_desc = self.first_method_descs.get(src_name).and_then(|desc| desc.as_deref());
+
+            if let Some(tag) = (self.identity)(&class) {
+                let renamed = format!("{}${}", class.outer_name, tag);
+
+                if !self.class_names.contains(&renamed) && self.renamed_classes.insert(renamed.clone()) {
+                    self.current_tag = Some(tag);
+                    return next.visit_class(&renamed);
+                }
+            }
+        }
+
+        next.visit_class(src_name)
+    }
+
+    fn visit_end<V: MappingVisitor>(&mut self, next: &mut V) -> VisitResult<bool> {
+        self.renamed_classes.clear();
+        next.visit_end()
+    }
+
+    fn visit_dst_name<V: MappingVisitor>(&mut self, next: &mut V, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if let (MappedElementKind::Class, Some(tag)) = (target_kind, &self.current_tag) {
+            if let Some(class) = AnonymousClass::parse(name) {
+                let renamed = format!("{}${}", class.outer_name, tag);
+                return next.visit_dst_name(target_kind, namespace, &renamed);
+            }
+        }
+
+        next.visit_dst_name(target_kind, namespace, name)
+    }
+}
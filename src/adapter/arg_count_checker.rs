@@ -0,0 +1,180 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::{anyhow, bail};
+use crate::MappedElementKind;
+use crate::descriptor::MethodDescriptor;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that checks that method arguments fit into the parameter lists of their methods.
+///
+/// The source descriptor of each method is parsed, and visiting an argument whose `arg_position`
+/// is not in `0..param_count` results in an error naming the class and method.
+/// Unknown positions (negative values) are not checked, unless the checker is created with
+/// [`strict`][Self::strict]. Strict checking suits writers that index a fixed-size array of arguments
+/// by position. Producers that only supply local variable indices, such as Tiny v2 files,
+/// can be combined with an [`ArgIndexConverter`](super::ArgIndexConverter) to fill in the positions first.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::ArgCountChecker;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut checker = ArgCountChecker::new(MappingTree::new());
+/// checker.visit_namespaces("official", &["named"])?;
+/// checker.visit_class("a")?;
+/// checker.visit_method("b", Some("(II)V"))?;
+/// assert!(checker.visit_method_arg(1, 2, None).is_ok());
+/// assert!(checker.visit_method_arg(2, 3, None).is_err());
+/// assert!(checker.visit_method_arg(-1, 1, None).is_ok());
+///
+/// let mut checker = ArgCountChecker::strict(MappingTree::new());
+/// checker.visit_namespaces("official", &["named"])?;
+/// checker.visit_class("a")?;
+/// checker.visit_method("b", Some("(II)V"))?;
+/// let error = checker.visit_method_arg(-1, 1, None).unwrap_err();
+/// assert_eq!(error.to_string(), "Argument position -1 of method a.b(II)V is out of bounds for 2 parameters");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ArgCountChecker<V> {
+    next: V,
+    strict: bool,
+    class_src_name: String,
+    method: Option<(String, String, usize)>,
+}
+
+impl<V: MappingVisitor> ArgCountChecker<V> {
+    /// Creates a new argument count checker that allows unknown positions.
+    pub fn new(next: V) -> Self {
+        ArgCountChecker { next, strict: false, class_src_name: String::new(), method: None }
+    }
+
+    /// Creates a new argument count checker that also rejects unknown (negative) positions.
+    pub fn strict(next: V) -> Self {
+        ArgCountChecker { next, strict: true, class_src_name: String::new(), method: None }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for ArgCountChecker<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        let mut flags = self.next.flags();
+        flags.insert(MappingFlag::NeedsSrcMethodDesc);
+        flags
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.method = None;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_src_name = src_name.to_owned();
+        self.method = None;
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.method = None;
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = src_desc.ok_or_else(|| anyhow!("ArgCountChecker needs src desc!"))?;
+        let param_count = MethodDescriptor::parse(src_desc)?.params().len();
+        self.method = Some((src_name.to_owned(), src_desc.to_owned(), param_count));
+        self.next.visit_method(src_name, Some(src_desc))
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        if let Some((method_name, method_desc, param_count)) = &self.method {
+            let out_of_bounds = if arg_position < 0 { self.strict } else { arg_position as usize >= *param_count };
+
+            if out_of_bounds {
+                bail!(
+                    "Argument position {} of method {}.{}{} is out of bounds for {} parameters",
+                    arg_position, self.class_src_name, method_name, method_desc, param_count
+                );
+            }
+        }
+
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.method = None;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,182 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::anyhow;
+use crate::MappedElementKind;
+use crate::descriptor::MethodDescriptor;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that fills in missing argument positions or local variable indices of method arguments.
+///
+/// The local variable indices of the parameters are computed from the source descriptor of each method
+/// using [`MethodDescriptor::arg_lv_indices`]. An argument with only an `arg_position` gets the matching
+/// `lv_index` and vice versa. Values that don't match any parameter are passed on unchanged.
+///
+/// Mappings don't record whether methods are static, so all methods are assumed to be instance methods
+/// (where index 0 holds `this`) unless they are listed using [`with_static_methods`][Self::with_static_methods].
+/// Static initializers (`<clinit>`) are always static.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::ArgIndexConverter;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut converter = ArgIndexConverter::new(MappingTree::new());
+/// converter.visit_namespaces("official", &["named"])?;
+/// converter.visit_class("a")?;
+/// converter.visit_element_content(MappedElementKind::Class)?;
+/// converter.visit_method("b", Some("(JI)V"))?;
+/// converter.visit_element_content(MappedElementKind::Method)?;
+/// converter.visit_method_arg(1, -1, None)?;
+/// converter.visit_dst_name(MappedElementKind::MethodArg, 0, "count")?;
+/// converter.visit_element_content(MappedElementKind::MethodArg)?;
+/// converter.visit_end()?;
+///
+/// let tree = converter.into_inner();
+/// let arg = &tree.class("a").unwrap().methods()[0].args()[0];
+/// assert_eq!((arg.arg_position(), arg.lv_index()), (1, 3));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ArgIndexConverter<V> {
+    next: V,
+    static_methods: HashSet<(String, String, String)>,
+    class_src_name: String,
+    lv_indices: Vec<usize>,
+}
+
+impl<V: MappingVisitor> ArgIndexConverter<V> {
+    /// Creates a new argument index converter.
+    pub fn new(next: V) -> Self {
+        ArgIndexConverter { next, static_methods: HashSet::new(), class_src_name: String::new(), lv_indices: Vec::new() }
+    }
+
+    /// Sets the static methods as tuples of their owners' source names, source names and source descriptors.
+    pub fn with_static_methods(mut self, static_methods: HashSet<(String, String, String)>) -> Self {
+        self.static_methods = static_methods;
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for ArgIndexConverter<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        let mut flags = self.next.flags();
+        flags.insert(MappingFlag::NeedsSrcMethodDesc);
+        flags
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_src_name = src_name.to_owned();
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let desc = src_desc.ok_or_else(|| anyhow!("ArgIndexConverter needs src desc!"))?;
+        let is_static = src_name == "<clinit>"
+            || self.static_methods.contains(&(self.class_src_name.clone(), src_name.to_owned(), desc.to_owned()));
+        self.lv_indices = MethodDescriptor::parse(desc)?.arg_lv_indices(is_static);
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        let mut arg_position = arg_position;
+        let mut lv_index = lv_index;
+
+        if arg_position >= 0 && lv_index < 0 {
+            if let Some(&index) = self.lv_indices.get(arg_position as usize) {
+                lv_index = index as i32;
+            }
+        } else if lv_index >= 0 && arg_position < 0 {
+            if let Some(position) = self.lv_indices.iter().position(|&index| index == lv_index as usize) {
+                arg_position = position as i32;
+            }
+        }
+
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
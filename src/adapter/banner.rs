@@ -0,0 +1,152 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that adds a banner of metadata entries to the header, such as the tool that produced the mappings.
+///
+/// The entries are visited right after the namespaces, before any metadata of the input.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::BannerVisitor;
+/// use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n";
+/// let mut output = String::new();
+/// let mut visitor = BannerVisitor::new(Tiny2Writer::new(&mut output))
+///     .with_entry("tool", "my-remapper")
+///     .with_entry("version", "1.0.0");
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+/// drop(visitor);
+///
+/// assert!(output.starts_with("tiny\tv2\t0\tofficial\tnamed\n\ttool\tmy-remapper\n\tversion\t1.0.0\nc\ta"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct BannerVisitor<V> {
+    next: V,
+    entries: Vec<(String, String)>,
+}
+
+impl<V: MappingVisitor> BannerVisitor<V> {
+    /// Creates a new banner visitor without any entries.
+    pub fn new(next: V) -> Self {
+        BannerVisitor { next, entries: Vec::new() }
+    }
+
+    /// Adds a metadata entry to the banner.
+    pub fn with_entry(mut self, key: &str, value: &str) -> Self {
+        self.entries.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for BannerVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)?;
+
+        for (key, value) in &self.entries {
+            self.next.visit_metadata(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,171 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use crate::MappedElementKind;
+use crate::adapter::{TwoPassAdapter, TwoPassVisitor};
+use crate::visitor::{MappingVisitor, VisitResult};
+
+/// The destination names of a method by their namespace indices.
+type DstNames = Vec<(usize, String)>;
+
+/// A synthetic bridge method and the method it delegates to, as used by [`BridgeMethodVisitor`].
+///
+/// Both methods have the same name, and the bridge typically has a less specific return type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BridgeMethod {
+    /// The source name of the class containing both methods.
+    pub owner: String,
+    /// The source descriptor of the bridge method.
+    pub bridge_desc: String,
+    /// The source descriptor of the target method.
+    pub target_desc: String,
+}
+
+/// A visitor that copies the destination names of methods to their synthetic bridge methods.
+///
+/// Since mappings don't contain any bytecode information, the bridge methods are supplied as a list
+/// of [`BridgeMethod`]s. For each method matching a bridge, the destination names of the method with the same name
+/// and the target descriptor are passed on in the namespaces where the bridge doesn't have its own name.
+/// This visitor needs an additional visitation pass to collect the names of the target methods first.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::{BridgeMethod, BridgeMethodVisitor};
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tm\t()Ljava/lang/Object;\tb\n\
+///             \tm\t()La;\tb\tcopy\n";
+/// let bridge = BridgeMethod {
+///     owner: "a".to_owned(),
+///     bridge_desc: "()Ljava/lang/Object;".to_owned(),
+///     target_desc: "()La;".to_owned(),
+/// };
+/// let mut visitor = BridgeMethodVisitor::new(MappingTree::new(), vec![bridge]);
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.method("b", Some("()Ljava/lang/Object;")).unwrap().dst_name(0), Some("copy"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub type BridgeMethodVisitor<V> = TwoPassVisitor<BridgeMethodAdapter, V>;
+
+/// The collection and rewrite logic of a [`BridgeMethodVisitor`].
+pub struct BridgeMethodAdapter {
+    /// The target descriptors by the owner and the bridge descriptor.
+    bridges: HashMap<(String, String), String>,
+    /// The owners and descriptors of the target methods.
+    targets: HashSet<(String, String)>,
+    /// The destination names of the target methods by their owner, name and descriptor, collected in the first pass.
+    target_names: HashMap<(String, String, String), DstNames>,
+    current_class: String,
+    /// The key of the target method that is being collected.
+    current_target: Option<(String, String, String)>,
+    /// The destination names to copy to the current bridge method, and whether it has a name in each namespace.
+    current_bridge: Option<(DstNames, HashSet<usize>)>,
+}
+
+impl<V: MappingVisitor> BridgeMethodVisitor<V> {
+    /// Creates a new bridge method visitor for the bridge methods `bridges`.
+    pub fn new(next: V, bridges: Vec<BridgeMethod>) -> Self {
+        let targets = bridges.iter()
+            .map(|bridge| (bridge.owner.clone(), bridge.target_desc.clone()))
+            .collect();
+        let bridges = bridges.into_iter()
+            .map(|bridge| ((bridge.owner, bridge.bridge_desc), bridge.target_desc))
+            .collect();
+
+        TwoPassVisitor::with_adapter(next, BridgeMethodAdapter {
+            bridges,
+            targets,
+            target_names: HashMap::new(),
+            current_class: String::new(),
+            current_target: None,
+            current_bridge: None,
+        })
+    }
+}
+
+impl TwoPassAdapter for BridgeMethodAdapter {
+    fn reset(&mut self) {
+        self.target_names.clear();
+        self.current_target = None;
+        self.current_bridge = None;
+    }
+
+    fn collect_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.current_class = src_name.to_owned();
+        Ok(true)
+    }
+
+    fn collect_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.current_target = src_desc
+            .map(|desc| (self.current_class.clone(), desc.to_owned()))
+            .filter(|key| self.targets.contains(key))
+            .map(|(owner, desc)| (owner, src_name.to_owned(), desc));
+        Ok(self.current_target.is_some())
+    }
+
+    fn collect_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if let (MappedElementKind::Method, Some(target)) = (target_kind, &self.current_target) {
+            self.target_names.entry(target.clone()).or_default().push((namespace, name.to_owned()));
+        }
+
+        Ok(())
+    }
+
+    fn visit_class<V: MappingVisitor>(&mut self, next: &mut V, src_name: &str) -> VisitResult<bool> {
+        self.current_class = src_name.to_owned();
+        next.visit_class(src_name)
+    }
+
+    fn visit_method<V: MappingVisitor>(&mut self, next: &mut V, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.current_bridge = src_desc
+            .and_then(|desc| self.bridges.get(&(self.current_class.clone(), desc.to_owned())))
+            .and_then(|target_desc| {
+                self.target_names.get(&(self.current_class.clone(), src_name.to_owned(), target_desc.clone()))
+            })
+            .map(|names| (names.clone(), HashSet::new()));
+        next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_dst_name<V: MappingVisitor>(&mut self, next: &mut V, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if let (MappedElementKind::Method, Some((_, named_namespaces))) = (target_kind, &mut self.current_bridge) {
+            named_namespaces.insert(namespace);
+        }
+
+        next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_element_content<V: MappingVisitor>(&mut self, next: &mut V, target_kind: MappedElementKind) -> VisitResult<bool> {
+        if target_kind == MappedElementKind::Method {
+            if let Some((names, named_namespaces)) = self.current_bridge.take() {
+                for (namespace, name) in names {
+                    if !named_namespaces.contains(&namespace) {
+                        next.visit_dst_name(MappedElementKind::Method, namespace, &name)?;
+                    }
+                }
+            }
+        }
+
+        next.visit_element_content(target_kind)
+    }
+}
@@ -0,0 +1,178 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::Context;
+use crate::MappedElementKind;
+use crate::descriptor::{MethodDescriptor, Type};
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that parses all descriptors and passes them on in their canonical form.
+///
+/// Some non-canonical forms found in hand-written mappings are tolerated and fixed:
+/// whitespace is removed, and primitive arrays written as object types (`L[I;`) become plain array types (`[I`).
+/// Descriptors that can't be parsed result in an error, so the next visitor only receives valid descriptors.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::CanonicalizeDescVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut visitor = CanonicalizeDescVisitor::new(MappingTree::new());
+/// visitor.visit_namespaces("official", &["named"])?;
+/// visitor.visit_class("a")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_method("b", Some("( L[I; Ljava/lang/String; ) V"))?;
+/// visitor.visit_element_content(MappedElementKind::Method)?;
+/// assert!(visitor.visit_field("c", Some("Ljava/lang/String")).is_err());
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.methods()[0].src_desc(), Some("([ILjava/lang/String;)V"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct CanonicalizeDescVisitor<V> {
+    next: V,
+}
+
+impl<V: MappingVisitor> CanonicalizeDescVisitor<V> {
+    /// Creates a new descriptor canonicalizing visitor.
+    pub fn new(next: V) -> Self {
+        CanonicalizeDescVisitor { next }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+/// Parses a field or method descriptor and returns its canonical form.
+fn canonicalize_desc(desc: &str) -> VisitResult<String> {
+    let stripped: String = desc.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let canonical = if stripped.starts_with('(') {
+        MethodDescriptor::parse(&stripped).and_then(|parsed| {
+            let params = parsed.params().iter().cloned().map(canonicalize_type).collect::<anyhow::Result<Vec<_>>>()?;
+            let return_type = canonicalize_type(parsed.return_type().clone())?;
+            Ok(MethodDescriptor::new(params, return_type).descriptor())
+        })
+    } else {
+        Type::from_descriptor(&stripped).and_then(canonicalize_type).map(|parsed| parsed.descriptor())
+    };
+
+    canonical.with_context(|| format!("Invalid descriptor {}", desc))
+}
+
+/// Replaces object types that name an array type (`L[I;`) with the array type itself.
+fn canonicalize_type(ty: Type) -> anyhow::Result<Type> {
+    match ty {
+        Type::Object(class) if class.internal_name().starts_with('[') => {
+            Type::from_descriptor(class.internal_name()).and_then(canonicalize_type)
+        }
+        Type::Array(element_type) => Ok(Type::Array(Box::new(canonicalize_type(*element_type)?))),
+        ty => Ok(ty),
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for CanonicalizeDescVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = src_desc.map(canonicalize_desc).transpose()?;
+        self.next.visit_field(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = src_desc.map(canonicalize_desc).transpose()?;
+        self.next.visit_method(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        let desc = canonicalize_desc(desc)?;
+        self.next.visit_dst_desc(target_kind, namespace, &desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,173 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that omits destination names that are equal to the source names of their elements.
+///
+/// Omitting a destination name isn't equivalent to an identity mapping for every consumer,
+/// so this is opt-in: only elements of the kinds passed to [`new`][Self::new] are affected.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::CollapseIdentityNamesVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut visitor = CollapseIdentityNamesVisitor::new(MappingTree::new(), &[MappedElementKind::Field]);
+/// visitor.visit_namespaces("official", &["named"])?;
+/// visitor.visit_class("a")?;
+/// visitor.visit_dst_name(MappedElementKind::Class, 0, "a")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_field("value", Some("I"))?;
+/// visitor.visit_dst_name(MappedElementKind::Field, 0, "value")?;
+/// visitor.visit_element_content(MappedElementKind::Field)?;
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("a"));
+/// assert_eq!(class.fields()[0].dst_name(0), None);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct CollapseIdentityNamesVisitor<V> {
+    next: V,
+    kinds: HashSet<MappedElementKind>,
+    class_src_name: String,
+    member_src_name: String,
+    local_src_name: Option<String>,
+}
+
+impl<V: MappingVisitor> CollapseIdentityNamesVisitor<V> {
+    /// Creates a new identity name collapsing visitor that applies to elements of the given kinds.
+    pub fn new(next: V, kinds: &[MappedElementKind]) -> Self {
+        CollapseIdentityNamesVisitor {
+            next,
+            kinds: kinds.iter().copied().collect(),
+            class_src_name: String::new(),
+            member_src_name: String::new(),
+            local_src_name: None,
+        }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn src_name(&self, kind: MappedElementKind) -> Option<&str> {
+        match kind {
+            MappedElementKind::Class => Some(&self.class_src_name),
+            MappedElementKind::Field | MappedElementKind::Method => Some(&self.member_src_name),
+            MappedElementKind::MethodArg | MappedElementKind::MethodVar => self.local_src_name.as_deref(),
+        }
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for CollapseIdentityNamesVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_src_name = src_name.to_owned();
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member_src_name = src_name.to_owned();
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member_src_name = src_name.to_owned();
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.local_src_name = src_name.map(str::to_owned);
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.local_src_name = src_name.map(str::to_owned);
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if self.kinds.contains(&target_kind) && self.src_name(target_kind) == Some(name) {
+            return Ok(());
+        }
+
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
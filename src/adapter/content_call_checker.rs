@@ -0,0 +1,173 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that checks that every accepted element gets a `visit_element_content` call.
+///
+/// After the wrapped visitor accepts an element, the producer must call `visit_element_content`
+/// before starting another element or ending the pass. Otherwise, the next visit results in an error.
+/// This catches producers that would make buffering writers drop elements.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::ContentCallChecker;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut checker = ContentCallChecker::new(MappingTree::new());
+/// checker.visit_namespaces("official", &["named"])?;
+/// checker.visit_class("a")?;
+/// checker.visit_dst_name(MappedElementKind::Class, 0, "com/example/Foo")?;
+/// checker.visit_element_content(MappedElementKind::Class)?;
+/// checker.visit_field("b", Some("I"))?;
+/// checker.visit_dst_name(MappedElementKind::Field, 0, "value")?;
+/// assert!(checker.visit_method("c", Some("()V")).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ContentCallChecker<V> {
+    next: V,
+    /// The kind and source name of the element that is waiting for its content visit.
+    pending: Option<(MappedElementKind, String)>,
+}
+
+impl<V: MappingVisitor> ContentCallChecker<V> {
+    /// Creates a new content call checker.
+    pub fn new(next: V) -> Self {
+        ContentCallChecker { next, pending: None }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn check_pending(&self) -> VisitResult<()> {
+        if let Some((kind, name)) = &self.pending {
+            bail!("visit_element_content was not called for {:?} {}", kind, name);
+        }
+
+        Ok(())
+    }
+
+    fn open(&mut self, kind: MappedElementKind, name: Option<&str>, visit: bool) -> VisitResult<bool> {
+        if visit {
+            self.pending = Some((kind, name.unwrap_or("<unnamed>").to_owned()));
+        }
+
+        Ok(visit)
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for ContentCallChecker<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.pending = None;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.check_pending()?;
+        let visit = self.next.visit_class(src_name)?;
+        self.open(MappedElementKind::Class, Some(src_name), visit)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.check_pending()?;
+        let visit = self.next.visit_field(src_name, src_desc)?;
+        self.open(MappedElementKind::Field, Some(src_name), visit)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.check_pending()?;
+        let visit = self.next.visit_method(src_name, src_desc)?;
+        self.open(MappedElementKind::Method, Some(src_name), visit)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.check_pending()?;
+        let visit = self.next.visit_method_arg(arg_position, lv_index, src_name)?;
+        self.open(MappedElementKind::MethodArg, src_name, visit)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.check_pending()?;
+        let visit = self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)?;
+        self.open(MappedElementKind::MethodVar, src_name, visit)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.check_pending()?;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.pending = None;
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
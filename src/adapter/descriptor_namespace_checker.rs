@@ -0,0 +1,170 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::descriptor::{MethodDescriptor, Type};
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that checks that source descriptors only reference known source classes.
+///
+/// Every object type in the source descriptors of fields and methods, including array element types,
+/// must be contained in the set of known source class names. Otherwise, visiting the member results in an error.
+/// This catches descriptors that have been left in another namespace.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::DescriptorNamespaceChecker;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let known = HashSet::from(["a".to_owned(), "java/lang/String".to_owned()]);
+/// let mut checker = DescriptorNamespaceChecker::new(MappingTree::new(), known);
+/// checker.visit_namespaces("official", &["named"])?;
+/// checker.visit_class("a")?;
+/// checker.visit_element_content(MappedElementKind::Class)?;
+/// assert!(checker.visit_method("b", Some("(La;[Ljava/lang/String;)V")).is_ok());
+/// assert!(checker.visit_field("c", Some("Lcom/example/Foo;")).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct DescriptorNamespaceChecker<V> {
+    next: V,
+    known_classes: HashSet<String>,
+}
+
+impl<V: MappingVisitor> DescriptorNamespaceChecker<V> {
+    /// Creates a new descriptor namespace checker with a set of known source class names.
+    pub fn new(next: V, known_classes: HashSet<String>) -> Self {
+        DescriptorNamespaceChecker { next, known_classes }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn check_type(&self, member: &str, desc: &str, mut ty: &Type) -> VisitResult<()> {
+        while let Type::Array(element_type) = ty {
+            ty = element_type;
+        }
+
+        if let Type::Object(class) = ty {
+            if !self.known_classes.contains(class.internal_name()) {
+                bail!("Descriptor {} of {} references unknown class {}", desc, member, class);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for DescriptorNamespaceChecker<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if let Some(desc) = src_desc {
+            self.check_type(src_name, desc, &Type::from_descriptor(desc)?)?;
+        }
+
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if let Some(desc) = src_desc {
+            let parsed = MethodDescriptor::parse(desc)?;
+
+            for ty in parsed.params().iter().chain([parsed.return_type()]) {
+                self.check_type(src_name, desc, ty)?;
+            }
+        }
+
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,177 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::{anyhow, bail};
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that removes a destination namespace by its name.
+///
+/// Destination names and descriptors in the dropped namespace are discarded,
+/// and the namespaces after it are moved down by one index.
+/// Dropping the source namespace or a namespace that doesn't exist results in an error.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::DropNamespaceVisitor;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tintermediary\tsrg\tnamed\n\
+///             c\ta\tclass_1\tC_1_\tcom/example/Foo\n\
+///             \tf\tI\tb\tfield_1\tf_1_\tcount\n";
+/// let mut visitor = DropNamespaceVisitor::new(MappingTree::new(), "srg");
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// assert_eq!(tree.dst_namespaces(), &["intermediary", "named"]);
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("class_1"));
+/// assert_eq!(class.dst_name(1), Some("com/example/Foo"));
+/// let field = class.field("b", Some("I")).unwrap();
+/// assert_eq!(field.dst_name(0), Some("field_1"));
+/// assert_eq!(field.dst_name(1), Some("count"));
+///
+/// let mut visitor = DropNamespaceVisitor::new(MappingTree::new(), "official");
+/// assert!(Tiny2Reader::read_str(tiny, &mut visitor).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct DropNamespaceVisitor<V> {
+    next: V,
+    namespace: String,
+    dropped_index: Option<usize>,
+}
+
+impl<V: MappingVisitor> DropNamespaceVisitor<V> {
+    /// Creates a new namespace dropping visitor that removes the destination namespace `namespace`.
+    pub fn new(next: V, namespace: &str) -> Self {
+        DropNamespaceVisitor { next, namespace: namespace.to_owned(), dropped_index: None }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    /// Maps an input namespace index to its output index, or `None` if it is dropped.
+    fn map_namespace(&self, namespace: usize) -> Option<usize> {
+        match self.dropped_index {
+            Some(dropped_index) if namespace == dropped_index => None,
+            Some(dropped_index) if namespace > dropped_index => Some(namespace - 1),
+            _ => Some(namespace),
+        }
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for DropNamespaceVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        if src_namespace == self.namespace {
+            bail!("Cannot drop the source namespace {}", src_namespace);
+        }
+
+        let dropped_index = dst_namespaces.iter().position(|&namespace| namespace == self.namespace)
+            .ok_or_else(|| anyhow!("Unknown destination namespace {}", self.namespace))?;
+        self.dropped_index = Some(dropped_index);
+
+        let mut remaining = dst_namespaces.to_vec();
+        remaining.remove(dropped_index);
+        self.next.visit_namespaces(src_namespace, &remaining)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        match self.map_namespace(namespace) {
+            Some(namespace) => self.next.visit_dst_name(target_kind, namespace, name),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        match self.map_namespace(namespace) {
+            Some(namespace) => self.next.visit_dst_desc(target_kind, namespace, desc),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,172 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that checks that no destination names or descriptors are visited after the element content.
+///
+/// [`visit_element_content`](MappingVisitor::visit_element_content) signals that all destination names
+/// and descriptors of an element have been passed. Visiting one of them for the same element afterwards
+/// results in an error, since buffering writers such as [`Tiny2Writer`](crate::format::tiny2::Tiny2Writer)
+/// would silently drop it. Correct sequences are passed on unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::DstAfterContentChecker;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut checker = DstAfterContentChecker::new(MappingTree::new());
+/// checker.visit_namespaces("official", &["named"])?;
+/// checker.visit_class("a")?;
+/// checker.visit_dst_name(MappedElementKind::Class, 0, "com/example/Foo")?;
+/// checker.visit_element_content(MappedElementKind::Class)?;
+/// checker.visit_field("b", Some("I"))?;
+/// checker.visit_element_content(MappedElementKind::Field)?;
+///
+/// let error = checker.visit_dst_name(MappedElementKind::Field, 0, "value").unwrap_err();
+/// assert_eq!(error.to_string(), "Destination name for Field b visited after its element content");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct DstAfterContentChecker<V> {
+    next: V,
+    /// The kind and source name of the current element, and whether its content has been visited.
+    current: Option<(MappedElementKind, String, bool)>,
+}
+
+impl<V: MappingVisitor> DstAfterContentChecker<V> {
+    /// Creates a new checker.
+    pub fn new(next: V) -> Self {
+        DstAfterContentChecker { next, current: None }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn check(&self, target_kind: MappedElementKind, what: &str) -> VisitResult<()> {
+        if let Some((kind, src_name, true)) = &self.current {
+            if *kind == target_kind {
+                bail!("Destination {} for {:?} {} visited after its element content", what, kind, src_name);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for DstAfterContentChecker<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.current = None;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.current = Some((MappedElementKind::Class, src_name.to_owned(), false));
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.current = Some((MappedElementKind::Field, src_name.to_owned(), false));
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.current = Some((MappedElementKind::Method, src_name.to_owned(), false));
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.current = Some((MappedElementKind::MethodArg, src_name.unwrap_or("").to_owned(), false));
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.current = Some((MappedElementKind::MethodVar, src_name.unwrap_or("").to_owned(), false));
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.current = None;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.check(target_kind, "name")?;
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.check(target_kind, "descriptor")?;
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        if let Some((kind, _, content_visited)) = &mut self.current {
+            if *kind == target_kind {
+                *content_visited = true;
+            }
+        }
+
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,152 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use crate::MappedElementKind;
+use crate::descriptor::remap_descriptor;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that updates class references in destination descriptors after destination classes have been renamed.
+///
+/// The renames map old destination class names to new ones, as internal names, and are applied to
+/// the destination descriptors of fields and methods in all namespaces. Destination names are passed on unchanged,
+/// so this is meant to follow a pass that renamed the destination classes themselves.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::DstDescFixupVisitor;
+/// use jvm_obfuscation_mappings::events::{EventSink, MappingEvent};
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let renames = HashMap::from([("com/example/Old".to_owned(), "com/example/New".to_owned())]);
+/// let mut visitor = DstDescFixupVisitor::new(EventSink::new(), renames);
+/// visitor.visit_namespaces("official", &["named"])?;
+/// visitor.visit_class("a")?;
+/// visitor.visit_dst_name(MappedElementKind::Class, 0, "com/example/New")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_method("b", Some("(La;)V"))?;
+/// visitor.visit_dst_desc(MappedElementKind::Method, 0, "(Lcom/example/Old;)V")?;
+///
+/// let events = visitor.into_inner().into_events();
+/// assert_eq!(events.last(), Some(&MappingEvent::DstDesc {
+///     target_kind: MappedElementKind::Method,
+///     namespace: 0,
+///     desc: "(Lcom/example/New;)V".to_owned(),
+/// }));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct DstDescFixupVisitor<V> {
+    next: V,
+    renames: HashMap<String, String>,
+}
+
+impl<V: MappingVisitor> DstDescFixupVisitor<V> {
+    /// Creates a new destination descriptor fixing visitor with a map of old destination class names to new ones.
+    pub fn new(next: V, renames: HashMap<String, String>) -> Self {
+        DstDescFixupVisitor { next, renames }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for DstDescFixupVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        let desc = remap_descriptor(desc, |class| self.renames.get(class).cloned());
+        self.next.visit_dst_desc(target_kind, namespace, &desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
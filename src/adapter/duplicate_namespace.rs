@@ -0,0 +1,173 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::anyhow;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that adds a copy of a destination namespace under a new name.
+///
+/// The copy is appended after the existing destination namespaces,
+/// and receives the same destination names and descriptors as the original namespace.
+/// Input without the original namespace results in an error.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::DuplicateNamespaceVisitor;
+/// use jvm_obfuscation_mappings::format::srg::SrgReader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let mut visitor = DuplicateNamespaceVisitor::new(MappingTree::new(), "target", "copy");
+/// SrgReader::read_str("CL: a com/example/Foo\nFD: a/b com/example/Foo/count\n", &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// assert_eq!(tree.dst_namespaces(), &["target", "copy"]);
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("com/example/Foo"));
+/// assert_eq!(class.dst_name(1), Some("com/example/Foo"));
+/// assert_eq!(class.fields()[0].dst_name(1), Some("count"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct DuplicateNamespaceVisitor<V> {
+    next: V,
+    namespace: String,
+    duplicate_name: String,
+    /// The indices of the original namespace and its copy.
+    indices: Option<(usize, usize)>,
+}
+
+impl<V: MappingVisitor> DuplicateNamespaceVisitor<V> {
+    /// Creates a new visitor that copies the destination namespace `namespace` to a new namespace called `duplicate_name`.
+    pub fn new(next: V, namespace: &str, duplicate_name: &str) -> Self {
+        DuplicateNamespaceVisitor {
+            next,
+            namespace: namespace.to_owned(),
+            duplicate_name: duplicate_name.to_owned(),
+            indices: None,
+        }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for DuplicateNamespaceVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        let index = dst_namespaces.iter().position(|&namespace| namespace == self.namespace)
+            .ok_or_else(|| anyhow!("Unknown namespace {}", self.namespace))?;
+        self.indices = Some((index, dst_namespaces.len()));
+
+        let mut duplicated = dst_namespaces.to_vec();
+        duplicated.push(&self.duplicate_name);
+        self.next.visit_namespaces(src_namespace, &duplicated)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)?;
+
+        if let Some((original, duplicate)) = self.indices {
+            if namespace == original {
+                self.next.visit_dst_name(target_kind, duplicate, name)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)?;
+
+        if let Some((original, duplicate)) = self.indices {
+            if namespace == original {
+                self.next.visit_dst_desc(target_kind, duplicate, desc)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
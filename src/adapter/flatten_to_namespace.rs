@@ -0,0 +1,172 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashMap;
+use anyhow::anyhow;
+use crate::MappedElementKind;
+use crate::descriptor::remap_descriptor;
+use crate::adapter::{TwoPassAdapter, TwoPassVisitor};
+use crate::visitor::{MappingVisitor, VisitResult};
+
+/// A visitor that reduces its input to a single destination namespace.
+///
+/// Only the destination names in the chosen namespace are kept, and they are passed on
+/// as the only destination namespace. All other namespaces are discarded along with their descriptors.
+/// The destination descriptors of fields and methods are computed by remapping the source descriptors
+/// with the class mappings of the chosen namespace, so this visitor needs an additional visitation pass
+/// to collect the class mappings first. Choosing a namespace that doesn't exist results in an error.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::FlattenToNamespaceVisitor;
+/// use jvm_obfuscation_mappings::events::{EventSink, MappingEvent};
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tintermediary\tnamed\n\
+///             c\ta\tclass_1\tcom/example/Foo\n\
+///             \tm\t(La;)La;\tb\tmethod_1\tcopy\n";
+/// let mut visitor = FlattenToNamespaceVisitor::new(EventSink::new(), "named");
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+///
+/// let events = visitor.into_inner().into_events();
+/// assert!(events.contains(&MappingEvent::Namespaces {
+///     src_namespace: "official".to_owned(),
+///     dst_namespaces: vec!["named".to_owned()],
+/// }));
+/// assert!(events.contains(&MappingEvent::DstName {
+///     target_kind: MappedElementKind::Class,
+///     namespace: 0,
+///     name: "com/example/Foo".to_owned(),
+/// }));
+/// assert!(events.contains(&MappingEvent::DstDesc {
+///     target_kind: MappedElementKind::Method,
+///     namespace: 0,
+///     desc: "(Lcom/example/Foo;)Lcom/example/Foo;".to_owned(),
+/// }));
+/// assert!(!events.iter().any(|event| matches!(event, MappingEvent::DstName { name, .. } if name == "class_1")));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub type FlattenToNamespaceVisitor<V> = TwoPassVisitor<FlattenToNamespaceAdapter, V>;
+
+/// The collection and rewrite logic of a [`FlattenToNamespaceVisitor`].
+pub struct FlattenToNamespaceAdapter {
+    namespace: String,
+    namespace_index: usize,
+    /// The class mappings in the chosen namespace, collected in the first pass.
+    classes: HashMap<String, String>,
+    current_class: Option<String>,
+}
+
+impl<V: MappingVisitor> FlattenToNamespaceVisitor<V> {
+    /// Creates a new flattening visitor that only keeps the destination namespace `namespace`.
+    pub fn new(next: V, namespace: &str) -> Self {
+        TwoPassVisitor::with_adapter(next, FlattenToNamespaceAdapter {
+            namespace: namespace.to_owned(),
+            namespace_index: 0,
+            classes: HashMap::new(),
+            current_class: None,
+        })
+    }
+}
+
+impl FlattenToNamespaceAdapter {
+    fn find_namespace(&mut self, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.namespace_index = dst_namespaces.iter().position(|&namespace| namespace == self.namespace)
+            .ok_or_else(|| anyhow!("Unknown destination namespace {}", self.namespace))?;
+        Ok(())
+    }
+
+    fn map_desc(&self, desc: &str) -> String {
+        remap_descriptor(desc, |class| self.classes.get(class).cloned())
+    }
+}
+
+impl TwoPassAdapter for FlattenToNamespaceAdapter {
+    fn reset(&mut self) {
+        self.classes.clear();
+        self.current_class = None;
+    }
+
+    fn collect_header(&mut self) -> VisitResult<bool> {
+        Ok(true)
+    }
+
+    fn collect_namespaces(&mut self, _src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.find_namespace(dst_namespaces)
+    }
+
+    fn collect_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.current_class = Some(src_name.to_owned());
+        Ok(true)
+    }
+
+    fn collect_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if namespace != self.namespace_index {
+            return Ok(());
+        }
+
+        if let (MappedElementKind::Class, Some(class)) = (target_kind, &self.current_class) {
+            self.classes.insert(class.clone(), name.to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn collect_element_content(&mut self, _target_kind: MappedElementKind) -> VisitResult<bool> {
+        // Only the class mappings are needed in the first pass.
+        Ok(false)
+    }
+
+    fn visit_namespaces<V: MappingVisitor>(&mut self, next: &mut V, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.find_namespace(dst_namespaces)?;
+        next.visit_namespaces(src_namespace, &[&self.namespace])
+    }
+
+    fn visit_field<V: MappingVisitor>(&mut self, next: &mut V, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let visit = next.visit_field(src_name, src_desc)?;
+
+        if let (true, Some(src_desc)) = (visit, src_desc) {
+            next.visit_dst_desc(MappedElementKind::Field, 0, &self.map_desc(src_desc))?;
+        }
+
+        Ok(visit)
+    }
+
+    fn visit_method<V: MappingVisitor>(&mut self, next: &mut V, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let visit = next.visit_method(src_name, src_desc)?;
+
+        if let (true, Some(src_desc)) = (visit, src_desc) {
+            next.visit_dst_desc(MappedElementKind::Method, 0, &self.map_desc(src_desc))?;
+        }
+
+        Ok(visit)
+    }
+
+    fn visit_dst_name<V: MappingVisitor>(&mut self, next: &mut V, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if namespace != self.namespace_index {
+            return Ok(());
+        }
+
+        next.visit_dst_name(target_kind, 0, name)
+    }
+
+    fn visit_dst_desc<V: MappingVisitor>(&mut self, _next: &mut V, _target_kind: MappedElementKind, _namespace: usize, _desc: &str) -> VisitResult<()> {
+        // The destination descriptors are computed from the source descriptors in visit_field and visit_method.
+        Ok(())
+    }
+}
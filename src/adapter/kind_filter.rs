@@ -0,0 +1,188 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that only passes on the names of elements of the given kinds.
+///
+/// Elements of other kinds are kept as containers if they are the parents of a kept kind,
+/// and skipped otherwise:
+/// - Classes are always passed on, since all other elements are nested in them.
+/// - Methods are passed on if methods, method arguments or method variables are kept.
+/// - Fields, method arguments and method variables are skipped unless their kind is kept.
+///
+/// Containers that aren't kept themselves are passed on without their destination names,
+/// destination descriptors and comments.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::KindFilterVisitor;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tf\tI\tb\tcount\n\
+///             \tm\t()V\tc\trun\n";
+/// let mut visitor = KindFilterVisitor::new(MappingTree::new(), &[MappedElementKind::Method]);
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), None);
+/// assert!(class.fields().is_empty());
+/// assert_eq!(class.method("c", Some("()V")).unwrap().dst_name(0), Some("run"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct KindFilterVisitor<V> {
+    next: V,
+    kinds: HashSet<MappedElementKind>,
+}
+
+impl<V: MappingVisitor> KindFilterVisitor<V> {
+    /// Creates a new kind filtering visitor that keeps the element kinds `kinds`.
+    pub fn new(next: V, kinds: &[MappedElementKind]) -> Self {
+        KindFilterVisitor { next, kinds: kinds.iter().copied().collect() }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn keeps(&self, kind: MappedElementKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+
+    /// Checks whether any kind nested in `kind` is kept.
+    fn keeps_children(&self, kind: MappedElementKind) -> bool {
+        self.kinds.iter().any(|kept| kept.level() > kind.level())
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for KindFilterVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if !self.keeps(MappedElementKind::Field) {
+            return Ok(false);
+        }
+
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if !self.keeps(MappedElementKind::Method) && !self.keeps_children(MappedElementKind::Method) {
+            return Ok(false);
+        }
+
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        if !self.keeps(MappedElementKind::MethodArg) {
+            return Ok(false);
+        }
+
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        if !self.keeps(MappedElementKind::MethodVar) {
+            return Ok(false);
+        }
+
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if !self.keeps(target_kind) {
+            return Ok(());
+        }
+
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        if !self.keeps(target_kind) {
+            return Ok(());
+        }
+
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        if !self.keeps(target_kind) {
+            return Ok(());
+        }
+
+        self.next.visit_comment(target_kind, comment)
+    }
+}
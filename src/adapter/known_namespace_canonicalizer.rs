@@ -0,0 +1,167 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The canonical spellings of well-known namespace names used by [`KnownNamespaceCanonicalizer`].
+pub const KNOWN_NAMESPACES: &[&str] = &["official", "intermediary", "named", "srg", "mojang"];
+
+/// A visitor that rewrites namespace names matching a [known namespace](KNOWN_NAMESPACES)
+/// case-insensitively to its canonical spelling.
+///
+/// Unknown namespaces are passed on unchanged, or result in an error
+/// if [`with_error_on_unknown`][Self::with_error_on_unknown] is set.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::KnownNamespaceCanonicalizer;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut visitor = KnownNamespaceCanonicalizer::new(MappingTree::new());
+/// visitor.visit_namespaces("OFFICIAL", &["Named", "myNamespace"])?;
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// assert_eq!(tree.src_namespace(), Some("official"));
+/// assert_eq!(tree.dst_namespaces(), &["named", "myNamespace"]);
+///
+/// let mut visitor = KnownNamespaceCanonicalizer::new(MappingTree::new()).with_error_on_unknown(true);
+/// let error = visitor.visit_namespaces("OFFICIAL", &["myNamespace"]).unwrap_err();
+/// assert_eq!(error.to_string(), "Unknown namespace myNamespace");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct KnownNamespaceCanonicalizer<V> {
+    next: V,
+    error_on_unknown: bool,
+}
+
+impl<V: MappingVisitor> KnownNamespaceCanonicalizer<V> {
+    /// Creates a new canonicalizer that passes unknown namespaces on unchanged.
+    pub fn new(next: V) -> Self {
+        KnownNamespaceCanonicalizer { next, error_on_unknown: false }
+    }
+
+    /// Sets whether unknown namespaces result in an error.
+    pub fn with_error_on_unknown(mut self, error_on_unknown: bool) -> Self {
+        self.error_on_unknown = error_on_unknown;
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    /// Returns the canonical spelling of a namespace name.
+    fn canonicalize<'a>(&self, namespace: &'a str) -> VisitResult<&'a str> {
+        match KNOWN_NAMESPACES.iter().find(|known| known.eq_ignore_ascii_case(namespace)) {
+            Some(known) => Ok(known),
+            None if self.error_on_unknown => bail!("Unknown namespace {}", namespace),
+            None => Ok(namespace),
+        }
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for KnownNamespaceCanonicalizer<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        let src_namespace = self.canonicalize(src_namespace)?;
+        let dst_namespaces = dst_namespaces.iter()
+            .map(|namespace| self.canonicalize(namespace))
+            .collect::<VisitResult<Vec<_>>>()?;
+        self.next.visit_namespaces(src_namespace, &dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
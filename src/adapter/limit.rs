@@ -0,0 +1,192 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that limits the number of classes, fields and methods in a visitation pass.
+///
+/// As soon as one of the limits is exceeded, the offending visit returns an error
+/// instead of being passed on. This can be used to guard against excessively large untrusted input.
+/// All limits are unset by default.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::LimitVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut limiter = LimitVisitor::new(MappingTree::new()).with_max_classes(2);
+/// limiter.visit_namespaces("official", &["named"])?;
+/// assert!(limiter.visit_class("a").is_ok());
+/// assert!(limiter.visit_class("b").is_ok());
+/// assert!(limiter.visit_class("c").is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct LimitVisitor<V> {
+    next: V,
+    max_classes: Option<usize>,
+    max_fields: Option<usize>,
+    max_methods: Option<usize>,
+    class_count: usize,
+    field_count: usize,
+    method_count: usize,
+}
+
+impl<V: MappingVisitor> LimitVisitor<V> {
+    /// Creates a new limit visitor without any limits.
+    pub fn new(next: V) -> Self {
+        LimitVisitor {
+            next,
+            max_classes: None,
+            max_fields: None,
+            max_methods: None,
+            class_count: 0,
+            field_count: 0,
+            method_count: 0,
+        }
+    }
+
+    /// Sets the maximum number of classes.
+    pub fn with_max_classes(mut self, max_classes: usize) -> Self {
+        self.max_classes = Some(max_classes);
+        self
+    }
+
+    /// Sets the maximum number of fields across all classes.
+    pub fn with_max_fields(mut self, max_fields: usize) -> Self {
+        self.max_fields = Some(max_fields);
+        self
+    }
+
+    /// Sets the maximum number of methods across all classes.
+    pub fn with_max_methods(mut self, max_methods: usize) -> Self {
+        self.max_methods = Some(max_methods);
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+/// Increments an element count, failing if it exceeds the limit.
+fn count(count: &mut usize, limit: Option<usize>, kind: &str) -> VisitResult<()> {
+    *count += 1;
+
+    if let Some(limit) = limit {
+        if *count > limit {
+            bail!("Mapping exceeds the limit of {} {}", limit, kind);
+        }
+    }
+
+    Ok(())
+}
+
+impl<V: MappingVisitor> MappingVisitor for LimitVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.class_count = 0;
+        self.field_count = 0;
+        self.method_count = 0;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        count(&mut self.class_count, self.max_classes, "classes")?;
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        count(&mut self.field_count, self.max_fields, "fields")?;
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        count(&mut self.method_count, self.max_methods, "methods")?;
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.class_count = 0;
+        self.field_count = 0;
+        self.method_count = 0;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
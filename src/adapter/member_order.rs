@@ -0,0 +1,349 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The order of member groups in a [`MemberOrderVisitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemberOrder {
+    /// All fields are visited before all methods.
+    FieldsFirst,
+    /// All methods are visited before all fields.
+    MethodsFirst,
+}
+
+/// A buffered visit belonging to a member or its children.
+enum MemberEvent {
+    Member(MappedElementKind, String, Option<String>),
+    Arg(i32, i32, Option<String>),
+    Var(i32, i32, i32, Option<String>),
+    DstName(MappedElementKind, usize, String),
+    DstDesc(MappedElementKind, usize, String),
+    ElementContent(MappedElementKind),
+    Comment(MappedElementKind, String),
+}
+
+/// Returns the source name and descriptor of a buffered member.
+fn signature(events: &[MemberEvent]) -> (&str, Option<&str>) {
+    match events.first() {
+        Some(MemberEvent::Member(_, src_name, src_desc)) => (src_name, src_desc.as_deref()),
+        _ => ("", None),
+    }
+}
+
+/// A visitor that groups the members of each class by kind.
+///
+/// The members of a class are buffered until the class ends, and then visited
+/// group by group in the chosen [`MemberOrder`]. Each group keeps the input order of its members,
+/// and method arguments, variables and comments stay attached to their members.
+///
+/// With [`with_sorted_signatures`][Self::with_sorted_signatures], the members of each group are instead
+/// sorted by their source name and descriptor, so that overloads such as `foo(I)V` and `foo(J)V`
+/// are always visited in the same order.
+///
+/// Since the members are buffered, their visit methods always return `true` to the producer.
+/// The results of the wrapped visitor are respected when the members are replayed.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::{MemberOrder, MemberOrderVisitor};
+/// use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tm\t()V\tb\trun\n\
+///             \t\tc\tRuns the thing.\n\
+///             \tf\tI\tc\tcount\n\
+///             \tm\t(I)V\td\tsetCount\n\
+///             \t\tp\t1\t\tcount\n\
+///             \tf\tZ\te\trunning\n";
+/// let mut output = String::new();
+/// let mut visitor = MemberOrderVisitor::new(Tiny2Writer::new(&mut output), MemberOrder::FieldsFirst);
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+/// drop(visitor);
+///
+/// assert_eq!(output, "tiny\tv2\t0\tofficial\tnamed\n\
+///                     c\ta\tcom/example/Foo\n\
+///                     \tf\tI\tc\tcount\n\
+///                     \tf\tZ\te\trunning\n\
+///                     \tm\t()V\tb\trun\n\
+///                     \t\tc\tRuns the thing.\n\
+///                     \tm\t(I)V\td\tsetCount\n\
+///                     \t\tp\t1\t\tcount\n");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Sorting overloads by descriptor:
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::{MemberOrder, MemberOrderVisitor};
+/// use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tm\t(J)V\tb\tset\n\
+///             \t\tp\t1\t\tvalue\n\
+///             \tm\t(I)V\tb\tset\n";
+/// let mut output = String::new();
+/// let mut visitor = MemberOrderVisitor::new(Tiny2Writer::new(&mut output), MemberOrder::FieldsFirst)
+///     .with_sorted_signatures(true);
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+/// drop(visitor);
+///
+/// assert_eq!(output, "tiny\tv2\t0\tofficial\tnamed\n\
+///                     c\ta\tcom/example/Foo\n\
+///                     \tm\t(I)V\tb\tset\n\
+///                     \tm\t(J)V\tb\tset\n\
+///                     \t\tp\t1\t\tvalue\n");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct MemberOrderVisitor<V> {
+    next: V,
+    order: MemberOrder,
+    sort_signatures: bool,
+    fields: Vec<Vec<MemberEvent>>,
+    methods: Vec<Vec<MemberEvent>>,
+    current_member: Option<MappedElementKind>,
+}
+
+impl<V: MappingVisitor> MemberOrderVisitor<V> {
+    /// Creates a new member order visitor.
+    pub fn new(next: V, order: MemberOrder) -> Self {
+        MemberOrderVisitor { next, order, sort_signatures: false, fields: Vec::new(), methods: Vec::new(), current_member: None }
+    }
+
+    /// Sets whether the members of each group are sorted by their source name and descriptor.
+    pub fn with_sorted_signatures(mut self, sort_signatures: bool) -> Self {
+        self.sort_signatures = sort_signatures;
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn start_member(&mut self, kind: MappedElementKind, src_name: &str, src_desc: Option<&str>) {
+        let event = MemberEvent::Member(kind, src_name.to_owned(), src_desc.map(str::to_owned));
+
+        if kind == MappedElementKind::Field {
+            self.fields.push(vec![event]);
+        } else {
+            self.methods.push(vec![event]);
+        }
+
+        self.current_member = Some(kind);
+    }
+
+    fn push(&mut self, event: MemberEvent) {
+        let members = match self.current_member {
+            Some(MappedElementKind::Field) => &mut self.fields,
+            _ => &mut self.methods,
+        };
+
+        if let Some(member) = members.last_mut() {
+            member.push(event);
+        }
+    }
+
+    /// Visits the buffered members of the current class in order.
+    fn flush(&mut self) -> VisitResult<()> {
+        let mut fields = std::mem::take(&mut self.fields);
+        let mut methods = std::mem::take(&mut self.methods);
+        self.current_member = None;
+
+        if self.sort_signatures {
+            fields.sort_by(|a, b| signature(a).cmp(&signature(b)));
+            methods.sort_by(|a, b| signature(a).cmp(&signature(b)));
+        }
+
+        let groups = match self.order {
+            MemberOrder::FieldsFirst => [fields, methods],
+            MemberOrder::MethodsFirst => [methods, fields],
+        };
+
+        for member in groups.iter().flatten() {
+            self.replay(member)?;
+        }
+
+        Ok(())
+    }
+
+    /// Visits the events of a single member, skipping elements the wrapped visitor isn't interested in.
+    fn replay(&mut self, events: &[MemberEvent]) -> VisitResult<()> {
+        // The level of the element whose remaining events and children are skipped.
+        let mut skip_level: Option<u32> = None;
+
+        for event in events {
+            let (kind, starts_element) = match event {
+                MemberEvent::Member(kind, _, _) => (*kind, true),
+                MemberEvent::Arg(..) => (MappedElementKind::MethodArg, true),
+                MemberEvent::Var(..) => (MappedElementKind::MethodVar, true),
+                MemberEvent::DstName(kind, _, _)
+                | MemberEvent::DstDesc(kind, _, _)
+                | MemberEvent::ElementContent(kind)
+                | MemberEvent::Comment(kind, _) => (*kind, false),
+            };
+
+            match skip_level {
+                Some(level) if kind.level() > level || (!starts_element && kind.level() == level) => continue,
+                _ => skip_level = None,
+            }
+
+            let visit = match event {
+                MemberEvent::Member(MappedElementKind::Field, name, desc) => self.next.visit_field(name, desc.as_deref())?,
+                MemberEvent::Member(_, name, desc) => self.next.visit_method(name, desc.as_deref())?,
+                MemberEvent::Arg(arg_position, lv_index, name) => self.next.visit_method_arg(*arg_position, *lv_index, name.as_deref())?,
+                MemberEvent::Var(lvt_row_index, lv_index, start_op_idx, name) => {
+                    self.next.visit_method_var(*lvt_row_index, *lv_index, *start_op_idx, name.as_deref())?
+                }
+                MemberEvent::DstName(kind, namespace, name) => {
+                    self.next.visit_dst_name(*kind, *namespace, name)?;
+                    true
+                }
+                MemberEvent::DstDesc(kind, namespace, desc) => {
+                    self.next.visit_dst_desc(*kind, *namespace, desc)?;
+                    true
+                }
+                MemberEvent::ElementContent(kind) => self.next.visit_element_content(*kind)?,
+                MemberEvent::Comment(kind, comment) => {
+                    self.next.visit_comment(*kind, comment)?;
+                    true
+                }
+            };
+
+            if !visit {
+                skip_level = Some(kind.level());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for MemberOrderVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.fields.clear();
+        self.methods.clear();
+        self.current_member = None;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.flush()?;
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.start_member(MappedElementKind::Field, src_name, src_desc);
+        Ok(true)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.start_member(MappedElementKind::Method, src_name, src_desc);
+        Ok(true)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.push(MemberEvent::Arg(arg_position, lv_index, src_name.map(str::to_owned)));
+        Ok(true)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.push(MemberEvent::Var(lvt_row_index, lv_index, start_op_idx, src_name.map(str::to_owned)));
+        Ok(true)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.flush()?;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Class {
+            return self.next.visit_dst_name(target_kind, namespace, name);
+        }
+
+        self.push(MemberEvent::DstName(target_kind, namespace, name.to_owned()));
+        Ok(())
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Class {
+            return self.next.visit_dst_desc(target_kind, namespace, desc);
+        }
+
+        self.push(MemberEvent::DstDesc(target_kind, namespace, desc.to_owned()));
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        if target_kind == MappedElementKind::Class {
+            return self.next.visit_element_content(target_kind);
+        }
+
+        self.push(MemberEvent::ElementContent(target_kind));
+        Ok(true)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Class {
+            return self.next.visit_comment(target_kind, comment);
+        }
+
+        self.push(MemberEvent::Comment(target_kind, comment.to_owned()));
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that records metadata properties so that they aren't lost when the next visitor can't store them.
+///
+/// All visited metadata properties are recorded and can be read using [`metadata`][Self::metadata].
+/// They are passed on if the next visitor [supports metadata](MappingVisitor::supports_metadata),
+/// such as a [`Tiny2Writer`](crate::format::tiny2::Tiny2Writer). Otherwise, they are only recorded,
+/// and [`lost_metadata`][Self::lost_metadata] lists them so that they can be stored separately.
+/// If the next visitor requests multiple passes, the metadata is only recorded during the first one.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::{FlattenToNamespaceVisitor, MetadataPreservingVisitor};
+/// use jvm_obfuscation_mappings::format::srg::SrgWriter;
+/// use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\tv2\t0\tofficial\tnamed\n\
+///             \tgenerator\texample 1.0\n\
+///             c\ta\tcom/example/Foo\n";
+/// let mut tree = MappingTree::new();
+/// Tiny2Reader::read_str(tiny, &mut tree)?;
+///
+/// let mut output = String::new();
+/// let mut visitor = MetadataPreservingVisitor::new(Tiny2Writer::new(&mut output));
+/// tree.accept(&mut visitor)?;
+/// assert!(visitor.lost_metadata().is_empty());
+/// drop(visitor);
+/// assert_eq!(output, tiny);
+///
+/// let mut srg = String::new();
+/// let mut visitor = MetadataPreservingVisitor::new(SrgWriter::new(&mut srg));
+/// tree.accept(&mut visitor)?;
+/// assert_eq!(visitor.lost_metadata(), &[("generator".to_owned(), "example 1.0".to_owned())]);
+///
+/// // The flattening visitor makes two passes, but the metadata is only recorded once.
+/// let mut visitor = MetadataPreservingVisitor::new(FlattenToNamespaceVisitor::new(MappingTree::new(), "named"));
+/// tree.accept(&mut visitor)?;
+/// assert_eq!(visitor.metadata(), &[("generator".to_owned(), "example 1.0".to_owned())]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct MetadataPreservingVisitor<V> {
+    next: V,
+    metadata: Vec<(String, String)>,
+    recording: bool,
+}
+
+impl<V: MappingVisitor> MetadataPreservingVisitor<V> {
+    /// Creates a new metadata preserving visitor.
+    pub fn new(next: V) -> Self {
+        MetadataPreservingVisitor { next, metadata: Vec::new(), recording: true }
+    }
+
+    /// Returns all visited metadata properties as key-value pairs in visitation order.
+    pub fn metadata(&self) -> &[(String, String)] {
+        &self.metadata
+    }
+
+    /// Returns the visited metadata properties that weren't passed on because the next visitor can't store them.
+    pub fn lost_metadata(&self) -> &[(String, String)] {
+        if self.next.supports_metadata() {
+            &[]
+        } else {
+            &self.metadata
+        }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for MetadataPreservingVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.metadata.clear();
+        self.recording = true;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        if self.recording {
+            self.metadata.push((key.to_owned(), value.to_owned()));
+        }
+
+        if self.next.supports_metadata() {
+            self.next.visit_metadata(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        // Later passes visit the same metadata again.
+        self.recording = false;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
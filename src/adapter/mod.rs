@@ -0,0 +1,101 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+mod anonymous_class_normalizer;
+mod arg_count_checker;
+mod arg_index_converter;
+mod banner;
+mod bridge_method;
+mod canonicalize_desc;
+mod collapse_identity_names;
+mod comment_loss_checker;
+mod content_call_checker;
+mod descriptor_namespace_checker;
+mod drop_namespace;
+mod dst_after_content_checker;
+mod dst_desc_fixup;
+mod duplicate_namespace;
+mod flatten_to_namespace;
+mod kind_filter;
+mod known_namespace_canonicalizer;
+mod limit;
+mod member_order;
+mod metadata_preserving;
+mod namespace_schema_checker;
+mod nested_separator;
+mod non_empty_name_checker;
+mod normalize_method_desc;
+mod obfuscate;
+mod orphan_members;
+mod package_mapping_applier;
+mod package_rename;
+mod profiling_writer;
+mod progress;
+mod proguard_field_desc;
+mod promote_namespace;
+mod rename;
+mod rename_namespace;
+mod simple_arg_model;
+mod slash_name_checker;
+mod source_annotating_comment;
+mod strip_synthetic;
+mod suffix_class_names;
+mod total_comment_budget;
+mod trim_names;
+mod two_pass;
+
+pub use anonymous_class_normalizer::*;
+pub use arg_count_checker::*;
+pub use arg_index_converter::*;
+pub use banner::*;
+pub use bridge_method::*;
+pub use canonicalize_desc::*;
+pub use collapse_identity_names::*;
+pub use comment_loss_checker::*;
+pub use content_call_checker::*;
+pub use descriptor_namespace_checker::*;
+pub use drop_namespace::*;
+pub use dst_after_content_checker::*;
+pub use dst_desc_fixup::*;
+pub use duplicate_namespace::*;
+pub use flatten_to_namespace::*;
+pub use kind_filter::*;
+pub use known_namespace_canonicalizer::*;
+pub use limit::*;
+pub use member_order::*;
+pub use metadata_preserving::*;
+pub use namespace_schema_checker::*;
+pub use nested_separator::*;
+pub use non_empty_name_checker::*;
+pub use normalize_method_desc::*;
+pub use obfuscate::*;
+pub use orphan_members::*;
+pub use package_mapping_applier::*;
+pub use package_rename::*;
+pub use profiling_writer::*;
+pub use progress::*;
+pub use proguard_field_desc::*;
+pub use promote_namespace::*;
+pub use rename::*;
+pub use rename_namespace::*;
+pub use simple_arg_model::*;
+pub use slash_name_checker::*;
+pub use source_annotating_comment::*;
+pub use strip_synthetic::*;
+pub use suffix_class_names::*;
+pub use total_comment_budget::*;
+pub use trim_names::*;
+pub use two_pass::*;
@@ -0,0 +1,167 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::descriptor::remap_descriptor;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that replaces the nested class separator `$` in class names with another character.
+///
+/// The replacement is applied to source and destination class names as well as class references
+/// in source and destination descriptors. Setting the separator to `/` turns nested classes
+/// into regular classes in a package named after their outer class.
+///
+/// The input is assumed to use `$` for nested classes and `/` only for packages,
+/// as internal names do. Every `$` in a class name is treated as a nested class separator.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::NestedSeparatorVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut visitor = NestedSeparatorVisitor::new(MappingTree::new(), '/');
+/// visitor.visit_namespaces("official", &["named"])?;
+/// visitor.visit_class("a/B$C")?;
+/// visitor.visit_dst_name(MappedElementKind::Class, 0, "com/example/Outer$Inner")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_field("f", Some("La/B$C;"))?;
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// let class = tree.class("a/B/C").unwrap();
+/// assert_eq!(class.dst_name(0), Some("com/example/Outer/Inner"));
+/// assert_eq!(class.fields()[0].src_desc(), Some("La/B/C;"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct NestedSeparatorVisitor<V> {
+    next: V,
+    separator: char,
+}
+
+impl<V: MappingVisitor> NestedSeparatorVisitor<V> {
+    /// Creates a new nested separator visitor that replaces `$` with `separator`.
+    pub fn new(next: V, separator: char) -> Self {
+        NestedSeparatorVisitor { next, separator }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn map_class(&self, name: &str) -> String {
+        name.replace('$', self.separator.encode_utf8(&mut [0; 4]))
+    }
+
+    fn map_desc(&self, desc: &str) -> String {
+        remap_descriptor(desc, |class| Some(self.map_class(class)))
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for NestedSeparatorVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        let src_name = self.map_class(src_name);
+        self.next.visit_class(&src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = src_desc.map(|desc| self.map_desc(desc));
+        self.next.visit_field(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = src_desc.map(|desc| self.map_desc(desc));
+        self.next.visit_method(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Class {
+            let name = self.map_class(name);
+            return self.next.visit_dst_name(target_kind, namespace, &name);
+        }
+
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        let desc = self.map_desc(desc);
+        self.next.visit_dst_desc(target_kind, namespace, &desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,189 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that checks that classes, fields and methods have non-empty source names.
+///
+/// Visiting such an element with an empty source name results in an error.
+/// Empty destination names can optionally be rejected as well using
+/// [`with_check_dst_names`][Self::with_check_dst_names].
+///
+/// If the reader reports [source positions](MappingVisitor::visit_source_position),
+/// the error messages include the line of the offending element.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::NonEmptyNameChecker;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut checker = NonEmptyNameChecker::new(MappingTree::new()).with_check_dst_names(true);
+/// checker.visit_namespaces("official", &["named"])?;
+/// assert!(checker.visit_class("").is_err());
+/// assert!(checker.visit_class("a").is_ok());
+/// assert!(checker.visit_dst_name(MappedElementKind::Class, 0, "").is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Reporting the line number from a reader:
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::NonEmptyNameChecker;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\nc\ta\tFoo\nc\t\tBar\n";
+/// let mut checker = NonEmptyNameChecker::new(MappingTree::new());
+/// let error = Tiny2Reader::read_str(tiny, &mut checker).unwrap_err();
+/// assert_eq!(error.root_cause().to_string(), "Empty class source name at line 3");
+/// ```
+pub struct NonEmptyNameChecker<V> {
+    next: V,
+    check_dst_names: bool,
+    line: Option<usize>,
+}
+
+impl<V: MappingVisitor> NonEmptyNameChecker<V> {
+    /// Creates a new name checker that only checks source names.
+    pub fn new(next: V) -> Self {
+        NonEmptyNameChecker { next, check_dst_names: false, line: None }
+    }
+
+    /// Sets whether empty destination names are rejected too.
+    pub fn with_check_dst_names(mut self, check_dst_names: bool) -> Self {
+        self.check_dst_names = check_dst_names;
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    /// Describes the current source position for error messages, or returns an empty string if it's unknown.
+    fn position(&self) -> String {
+        match self.line {
+            Some(line) => format!(" at line {}", line),
+            None => String::new(),
+        }
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for NonEmptyNameChecker<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.line = None;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.line = Some(line);
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        if src_name.is_empty() {
+            bail!("Empty class source name{}", self.position());
+        }
+
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if src_name.is_empty() {
+            bail!("Empty field source name{}", self.position());
+        }
+
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if src_name.is_empty() {
+            bail!("Empty method source name{}", self.position());
+        }
+
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if self.check_dst_names && name.is_empty() {
+            bail!("Empty destination name for {:?} in namespace {}{}", target_kind, namespace, self.position());
+        }
+
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,163 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::Context;
+use crate::MappedElementKind;
+use crate::descriptor::MethodDescriptor;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that re-parses all method descriptors and passes them on as re-serialized by [`MethodDescriptor`].
+///
+/// Both source and destination method descriptors are normalized, so the next visitor only receives
+/// canonical erased descriptors. Unlike [`CanonicalizeDescVisitor`][super::CanonicalizeDescVisitor],
+/// no malformed input is tolerated: any descriptor rejected by [`MethodDescriptor::parse`] results in
+/// an error naming the method. Field descriptors are passed on unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::NormalizeMethodDescVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut visitor = NormalizeMethodDescVisitor::new(MappingTree::new());
+/// visitor.visit_namespaces("official", &["named"])?;
+/// visitor.visit_class("a")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_method("b", Some("(ILjava/lang/String;)V"))?;
+/// visitor.visit_element_content(MappedElementKind::Method)?;
+///
+/// let error = visitor.visit_method("c", Some("(Ljava/util/List<Ljava/lang/String;>;)V")).unwrap_err();
+/// assert!(error.to_string().contains("method c"));
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// assert_eq!(tree.class("a").unwrap().methods()[0].src_desc(), Some("(ILjava/lang/String;)V"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct NormalizeMethodDescVisitor<V> {
+    next: V,
+    method_name: String,
+}
+
+impl<V: MappingVisitor> NormalizeMethodDescVisitor<V> {
+    /// Creates a new method descriptor normalizing visitor.
+    pub fn new(next: V) -> Self {
+        NormalizeMethodDescVisitor { next, method_name: String::new() }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+/// Parses the descriptor of the method `name` and returns its re-serialized form.
+fn normalize_method_desc(name: &str, desc: &str) -> VisitResult<String> {
+    let parsed = MethodDescriptor::parse(desc).with_context(|| format!("Invalid descriptor of method {}", name))?;
+    Ok(parsed.descriptor())
+}
+
+impl<V: MappingVisitor> MappingVisitor for NormalizeMethodDescVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.method_name = src_name.to_owned();
+        let src_desc = src_desc.map(|desc| normalize_method_desc(src_name, desc)).transpose()?;
+        self.next.visit_method(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Method {
+            let desc = normalize_method_desc(&self.method_name, desc)?;
+            return self.next.visit_dst_desc(target_kind, namespace, &desc);
+        }
+
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,228 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use crate::MappedElementKind;
+use crate::descriptor::remap_descriptor;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that generates deterministic obfuscated names for testing.
+///
+/// The input is mapped to a single destination namespace where classes are named `C<n>`,
+/// fields `f<n>` and methods `m<n>`, numbered in the order they are first encountered.
+/// Classes are also numbered when they are first referenced in a descriptor.
+/// Generated names are remembered across passes, so the same source element always gets the same name.
+/// Constructors and static initializers (`<init>` and `<clinit>`) can't be renamed, so they don't get a name.
+///
+/// The destination names of the input are discarded, and destination descriptors are generated from
+/// the source descriptors using the generated class names. Method arguments, variables and comments
+/// are passed on without destination names.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::adapter::ObfuscateVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tnamed\tofficial\n\
+///             c\tcom/example/Foo\ta\n\
+///             \tf\tLcom/example/Bar;\tbar\tb\n\
+///             \tm\t()V\t<init>\t<init>\n\
+///             \tm\t()V\trun\td\n\
+///             c\tcom/example/Bar\tc\n";
+/// let mut visitor = ObfuscateVisitor::new(MappingTree::new(), "obfuscated");
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// assert_eq!(tree.dst_namespaces(), &["obfuscated"]);
+/// assert_eq!(tree.class("com/example/Foo").unwrap().dst_name(0), Some("C0"));
+/// assert_eq!(tree.class("com/example/Bar").unwrap().dst_name(0), Some("C1"));
+/// let foo = tree.class("com/example/Foo").unwrap();
+/// assert_eq!(foo.fields()[0].dst_name(0), Some("f0"));
+/// assert_eq!(foo.method("<init>", Some("()V")).unwrap().dst_name(0), None);
+/// assert_eq!(foo.method("run", Some("()V")).unwrap().dst_name(0), Some("m0"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ObfuscateVisitor<V> {
+    next: V,
+    dst_namespace: String,
+    classes: HashMap<String, String>,
+    fields: HashMap<(String, String, Option<String>), String>,
+    methods: HashMap<(String, String, Option<String>), String>,
+    class_src_name: String,
+    member: Option<(String, Option<String>)>,
+}
+
+impl<V: MappingVisitor> ObfuscateVisitor<V> {
+    /// Creates a new obfuscating visitor that reports the generated names in the namespace `dst_namespace`.
+    pub fn new(next: V, dst_namespace: &str) -> Self {
+        ObfuscateVisitor {
+            next,
+            dst_namespace: dst_namespace.to_owned(),
+            classes: HashMap::new(),
+            fields: HashMap::new(),
+            methods: HashMap::new(),
+            class_src_name: String::new(),
+            member: None,
+        }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn class_name(&mut self, src_name: &str) -> String {
+        let count = self.classes.len();
+        self.classes.entry(src_name.to_owned()).or_insert_with(|| format!("C{}", count)).clone()
+    }
+
+    fn map_desc(&mut self, desc: &str) -> String {
+        remap_descriptor(desc, |class| Some(self.class_name(class)))
+    }
+}
+
+/// Gets or generates the name of a member.
+fn member_name(
+    names: &mut HashMap<(String, String, Option<String>), String>,
+    prefix: char,
+    owner: &str,
+    (name, desc): &(String, Option<String>),
+) -> String {
+    let count = names.len();
+    names.entry((owner.to_owned(), name.clone(), desc.clone()))
+        .or_insert_with(|| format!("{}{}", prefix, count))
+        .clone()
+}
+
+impl<V: MappingVisitor> MappingVisitor for ObfuscateVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        let mut flags = self.next.flags();
+        flags.remove(&MappingFlag::NeedsDstFieldDesc);
+        flags.remove(&MappingFlag::NeedsDstMethodDesc);
+        flags
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.member = None;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, _dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, &[&self.dst_namespace])
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_src_name = src_name.to_owned();
+        self.member = None;
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member = Some((src_name.to_owned(), src_desc.map(str::to_owned)));
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member = Some((src_name.to_owned(), src_desc.map(str::to_owned)));
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.member = None;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, _target_kind: MappedElementKind, _namespace: usize, _name: &str) -> VisitResult<()> {
+        Ok(())
+    }
+
+    fn visit_dst_desc(&mut self, _target_kind: MappedElementKind, _namespace: usize, _desc: &str) -> VisitResult<()> {
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        match (target_kind, &self.member) {
+            (MappedElementKind::Class, _) => {
+                let name = self.class_name(&self.class_src_name.clone());
+                self.next.visit_dst_name(target_kind, 0, &name)?;
+            }
+            (MappedElementKind::Field | MappedElementKind::Method, Some(member)) => {
+                let member = member.clone();
+                let name = if target_kind == MappedElementKind::Field {
+                    Some(member_name(&mut self.fields, 'f', &self.class_src_name, &member))
+                } else if member.0.starts_with('<') {
+                    None
+                } else {
+                    Some(member_name(&mut self.methods, 'm', &self.class_src_name, &member))
+                };
+
+                if let Some(name) = name {
+                    self.next.visit_dst_name(target_kind, 0, &name)?;
+                }
+
+                if let Some(desc) = &member.1 {
+                    let desc = self.map_desc(desc);
+                    self.next.visit_dst_desc(target_kind, 0, &desc)?;
+                }
+            }
+            _ => {}
+        }
+
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,259 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A field or method that was visited without an owner class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanMember {
+    /// The kind of the member, either [`Field`][MappedElementKind::Field] or [`Method`][MappedElementKind::Method].
+    pub kind: MappedElementKind,
+    /// The source name of the member.
+    pub src_name: String,
+    /// The source descriptor of the member.
+    pub src_desc: Option<String>,
+}
+
+/// A visitor that reports or repairs members visited without an owner class.
+///
+/// A member is an orphan if it's visited before any class of the content, or after the current class
+/// was skipped by the next visitor. Forwarding it would attach it to whichever class the next visitor
+/// visited last, or fail outright in the case of a [`MappingTree`][crate::tree::MappingTree].
+///
+/// By default, orphans are recorded and skipped. In repair mode, they're placed in a minimal entry
+/// of a given owner class instead, which has no destination names of its own. Since the real owners
+/// of orphans are unknown, two orphans with the same kind, name and descriptor would be merged
+/// in that class, so this results in an error.
+/// Classes that are visited but unmapped are never orphans, nor are their members.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::{OrphanMember, OrphanMemberChecker};
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// fn visit(visitor: &mut impl MappingVisitor) -> anyhow::Result<()> {
+///     visitor.visit_namespaces("official", &["named"])?;
+///     visitor.visit_content()?;
+///
+///     if visitor.visit_field("a", Some("I"))? {
+///         visitor.visit_dst_name(MappedElementKind::Field, 0, "count")?;
+///         visitor.visit_element_content(MappedElementKind::Field)?;
+///     }
+///
+///     // An unmapped class still owns its members.
+///     visitor.visit_class("b")?;
+///     visitor.visit_element_content(MappedElementKind::Class)?;
+///     visitor.visit_field("c", Some("I"))?;
+///     visitor.visit_element_content(MappedElementKind::Field)?;
+///     visitor.visit_end()?;
+///     Ok(())
+/// }
+///
+/// let mut checker = OrphanMemberChecker::new(MappingTree::new());
+/// visit(&mut checker)?;
+/// let orphan = OrphanMember { kind: MappedElementKind::Field, src_name: "a".to_owned(), src_desc: Some("I".to_owned()) };
+/// assert_eq!(checker.orphans(), &[orphan.clone()]);
+/// let tree = checker.into_inner();
+/// assert_eq!(tree.classes().len(), 1);
+/// assert_eq!(tree.class("b").unwrap().dst_name(0), None);
+///
+/// let mut checker = OrphanMemberChecker::repairing(MappingTree::new(), "Orphans");
+/// visit(&mut checker)?;
+/// assert_eq!(checker.orphans(), &[orphan]);
+/// let tree = checker.into_inner();
+/// let owner = tree.class("Orphans").unwrap();
+/// assert_eq!(owner.dst_name(0), None);
+/// assert_eq!(owner.field("a", Some("I")).unwrap().dst_name(0), Some("count"));
+///
+/// let mut checker = OrphanMemberChecker::repairing(MappingTree::new(), "Orphans");
+/// checker.visit_namespaces("official", &["named"])?;
+/// checker.visit_content()?;
+/// checker.visit_field("a", Some("I"))?;
+/// checker.visit_element_content(MappedElementKind::Field)?;
+/// let error = checker.visit_field("a", Some("I")).unwrap_err();
+/// assert_eq!(error.to_string(), "Orphan Field a I collides with an earlier orphan in the repair class Orphans");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct OrphanMemberChecker<V> {
+    next: V,
+    repair_owner: Option<String>,
+    orphans: Vec<OrphanMember>,
+    in_class: bool,
+    in_repair_owner: bool,
+}
+
+impl<V: MappingVisitor> OrphanMemberChecker<V> {
+    /// Creates a new orphan member checker that skips orphans.
+    pub fn new(next: V) -> Self {
+        OrphanMemberChecker { next, repair_owner: None, orphans: Vec::new(), in_class: false, in_repair_owner: false }
+    }
+
+    /// Creates a new orphan member checker that places orphans in the class `owner`.
+    pub fn repairing(next: V, owner: &str) -> Self {
+        OrphanMemberChecker { next, repair_owner: Some(owner.to_owned()), orphans: Vec::new(), in_class: false, in_repair_owner: false }
+    }
+
+    /// Returns the orphans found in the last content pass, in the order they were visited.
+    pub fn orphans(&self) -> &[OrphanMember] {
+        &self.orphans
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    /// Records a member if it's an orphan, and returns whether it should be forwarded.
+    fn check_member(&mut self, kind: MappedElementKind, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if self.in_class {
+            return Ok(true);
+        }
+
+        let orphan = OrphanMember { kind, src_name: src_name.to_owned(), src_desc: src_desc.map(str::to_owned) };
+
+        let Some(owner) = &self.repair_owner else {
+            self.orphans.push(orphan);
+            return Ok(false);
+        };
+
+        if self.orphans.contains(&orphan) {
+            bail!(
+                "Orphan {:?} {} {} collides with an earlier orphan in the repair class {}",
+                kind, src_name, src_desc.unwrap_or_default(), owner
+            );
+        }
+
+        self.orphans.push(orphan);
+
+        if !self.in_repair_owner {
+            self.in_repair_owner = self.next.visit_class(owner)? && self.next.visit_element_content(MappedElementKind::Class)?;
+        }
+
+        Ok(self.in_repair_owner)
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for OrphanMemberChecker<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.orphans.clear();
+        self.in_class = false;
+        self.in_repair_owner = false;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.orphans.clear();
+        self.in_class = false;
+        self.in_repair_owner = false;
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.in_repair_owner = false;
+        self.in_class = self.next.visit_class(src_name)?;
+        Ok(self.in_class)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if !self.check_member(MappedElementKind::Field, src_name, src_desc)? {
+            return Ok(false);
+        }
+
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if !self.check_member(MappedElementKind::Method, src_name, src_desc)? {
+            return Ok(false);
+        }
+
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.in_class = false;
+        self.in_repair_owner = false;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        let visit_content = self.next.visit_element_content(target_kind)?;
+
+        if target_kind == MappedElementKind::Class {
+            self.in_class = visit_content;
+        }
+
+        Ok(visit_content)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,184 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use crate::MappedElementKind;
+use crate::format::srg::SRG_PACKAGE_METADATA_PREFIX;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that applies SRG package mappings to classes without an explicit mapping.
+///
+/// Package mappings are read from metadata properties with keys starting with [`SRG_PACKAGE_METADATA_PREFIX`],
+/// as produced by [`SrgReader::read_with_packages`](crate::format::srg::SrgReader::read_with_packages).
+/// Classes without a destination name in the first destination namespace whose package exactly matches
+/// a package mapping get the mapped package and their unchanged simple name as their destination name.
+/// The default package is written as `.`.
+///
+/// Explicit class mappings take precedence over package mappings. Since the package mappings have to be known
+/// before the classes, and a class must not receive a destination name after it has been visited once,
+/// this visitor requires [uniqueness](MappingFlag::NeedsUniqueness).
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::PackageMappingApplier;
+/// use jvm_obfuscation_mappings::format::srg::SrgReader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let srg = "PK: com/old com/new\n\
+///            CL: com/old/Foo com/new/Renamed\n\
+///            MD: com/old/Bar/a ()V com/old/Bar/run ()V\n\
+///            FD: com/old/Baz/a com/old/Baz/value\n\
+///            FD: com/other/Qux/a com/other/Qux/value\n";
+/// let mut applier = PackageMappingApplier::new(MappingTree::new());
+/// SrgReader::read_with_packages(srg.as_bytes(), &mut applier)?;
+///
+/// let tree = applier.into_inner();
+/// assert_eq!(tree.class("com/old/Foo").unwrap().dst_name(0), Some("com/new/Renamed"));
+/// // Bar and Baz have no CL lines, so only the package mapping decides their names.
+/// assert_eq!(tree.class("com/old/Bar").unwrap().dst_name(0), Some("com/new/Bar"));
+/// assert_eq!(tree.class("com/old/Baz").unwrap().dst_name(0), Some("com/new/Baz"));
+/// assert_eq!(tree.class("com/other/Qux").unwrap().dst_name(0), None);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct PackageMappingApplier<V> {
+    next: V,
+    packages: HashMap<String, String>,
+    class_src_name: String,
+    has_dst_name: bool,
+}
+
+impl<V: MappingVisitor> PackageMappingApplier<V> {
+    /// Creates a new package mapping applier.
+    pub fn new(next: V) -> Self {
+        PackageMappingApplier { next, packages: HashMap::new(), class_src_name: String::new(), has_dst_name: false }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    /// Maps the package of a class, or returns `None` if there's no mapping for it.
+    fn map_package(&self, class: &str) -> Option<String> {
+        let (package, simple_name) = class.rsplit_once('/').unwrap_or((".", class));
+
+        match self.packages.get(package)?.as_str() {
+            "." => Some(simple_name.to_owned()),
+            to => Some(format!("{}/{}", to, simple_name)),
+        }
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for PackageMappingApplier<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        let mut flags = self.next.flags();
+        flags.insert(MappingFlag::NeedsUniqueness);
+        flags
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        if let Some(package) = key.strip_prefix(SRG_PACKAGE_METADATA_PREFIX) {
+            self.packages.insert(package.to_owned(), value.to_owned());
+        }
+
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_src_name = src_name.to_owned();
+        self.has_dst_name = false;
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Class && namespace == 0 {
+            self.has_dst_name = true;
+        }
+
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        if target_kind == MappedElementKind::Class && !self.has_dst_name {
+            if let Some(dst_name) = self.map_package(&self.class_src_name) {
+                self.next.visit_dst_name(MappedElementKind::Class, 0, &dst_name)?;
+            }
+        }
+
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
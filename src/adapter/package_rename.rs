@@ -0,0 +1,174 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::descriptor::remap_descriptor;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that moves all classes from one package to another.
+///
+/// The packages are specified as internal names (`com/example`). Classes in subpackages are moved as well,
+/// but packages are only matched on package boundaries: renaming `com/old` doesn't affect `com/oldish`.
+/// The rename is applied to source and destination class names and to class references in descriptors.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::PackageRenameVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut visitor = PackageRenameVisitor::new(MappingTree::new(), "com/old", "com/new");
+/// visitor.visit_namespaces("official", &["named"])?;
+/// visitor.visit_class("com/old/Foo")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_method("bar", Some("(Lcom/old/Foo;Lcom/oldish/Baz;)V"))?;
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// let class = tree.class("com/new/Foo").unwrap();
+/// assert_eq!(class.methods()[0].src_desc(), Some("(Lcom/new/Foo;Lcom/oldish/Baz;)V"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct PackageRenameVisitor<V> {
+    next: V,
+    from: String,
+    to: String,
+}
+
+impl<V: MappingVisitor> PackageRenameVisitor<V> {
+    /// Creates a new package rename visitor that moves classes from the package `from` to `to`.
+    pub fn new(next: V, from: &str, to: &str) -> Self {
+        PackageRenameVisitor { next, from: from.to_owned(), to: to.to_owned() }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    /// Renames the package of a class, or returns `None` if the class isn't in the renamed package.
+    fn rename(&self, class: &str) -> Option<String> {
+        let rest = class.strip_prefix(&self.from)?.strip_prefix('/')?;
+
+        if self.to.is_empty() {
+            Some(rest.to_owned())
+        } else {
+            Some(format!("{}/{}", self.to, rest))
+        }
+    }
+
+    fn map_class(&self, class: &str) -> String {
+        self.rename(class).unwrap_or_else(|| class.to_owned())
+    }
+
+    fn map_desc(&self, desc: &str) -> String {
+        remap_descriptor(desc, |class| self.rename(class))
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for PackageRenameVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        let src_name = self.map_class(src_name);
+        self.next.visit_class(&src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = src_desc.map(|desc| self.map_desc(desc));
+        self.next.visit_field(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = src_desc.map(|desc| self.map_desc(desc));
+        self.next.visit_method(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Class {
+            let name = self.map_class(name);
+            return self.next.visit_dst_name(target_kind, namespace, &name);
+        }
+
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        let desc = self.map_desc(desc);
+        self.next.visit_dst_desc(target_kind, namespace, &desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,245 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::format::ByteCounter;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The number of output bytes per element kind, as measured by a [`ProfilingWriter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ByteProfile {
+    /// Bytes written for the header, namespaces and metadata.
+    pub header: usize,
+    /// Bytes written for classes.
+    pub classes: usize,
+    /// Bytes written for fields.
+    pub fields: usize,
+    /// Bytes written for methods.
+    pub methods: usize,
+    /// Bytes written for method arguments.
+    pub args: usize,
+    /// Bytes written for method variables.
+    pub vars: usize,
+    /// Bytes written for comments of any element.
+    pub comments: usize,
+    /// Bytes written at the end of the visitation.
+    pub other: usize,
+}
+
+/// The part of a [`ByteProfile`] that a visit is attributed to.
+enum Bucket {
+    Header,
+    Element(MappedElementKind),
+    Comment,
+    Other,
+}
+
+impl ByteProfile {
+    fn add(&mut self, bucket: Bucket, bytes: usize) {
+        let total = match bucket {
+            Bucket::Header => &mut self.header,
+            Bucket::Element(MappedElementKind::Class) => &mut self.classes,
+            Bucket::Element(MappedElementKind::Field) => &mut self.fields,
+            Bucket::Element(MappedElementKind::Method) => &mut self.methods,
+            Bucket::Element(MappedElementKind::MethodArg) => &mut self.args,
+            Bucket::Element(MappedElementKind::MethodVar) => &mut self.vars,
+            Bucket::Comment => &mut self.comments,
+            Bucket::Other => &mut self.other,
+        };
+
+        *total += bytes;
+    }
+}
+
+/// A visitor that measures how many bytes a writer outputs for each element kind.
+///
+/// The output of the wrapped writer has to go through a [`CountingWrite`](crate::format::CountingWrite),
+/// whose [`ByteCounter`] is passed to this visitor. The bytes written during each visit are attributed
+/// to the element kind of the visit, and comments are counted separately. Writers that buffer elements
+/// until a later visit are measured when they actually write.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::ProfilingWriter;
+/// use jvm_obfuscation_mappings::format::CountingWrite;
+/// use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+///
+/// let tiny = "tiny\tv2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tm\t()V\tb\trun\n\
+///             \t\tc\tRuns the thing.\n";
+/// let mut output = String::new();
+/// let write = CountingWrite::new(&mut output);
+/// let counter = write.counter();
+/// let mut profiler = ProfilingWriter::new(Tiny2Writer::new(write), counter);
+/// Tiny2Reader::read_str(tiny, &mut profiler)?;
+///
+/// let profile = profiler.profile();
+/// assert_eq!(profile.classes, "c\ta\tcom/example/Foo\n".len());
+/// assert_eq!(profile.methods, "\tm\t()V\tb\trun\n".len());
+/// assert_eq!(profile.comments, "\t\tc\tRuns the thing.\n".len());
+/// assert_eq!(profile.fields, 0);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ProfilingWriter<V> {
+    next: V,
+    counter: ByteCounter,
+    profile: ByteProfile,
+}
+
+impl<V: MappingVisitor> ProfilingWriter<V> {
+    /// Creates a new profiling visitor that reads the output size of `next` from `counter`.
+    pub fn new(next: V, counter: ByteCounter) -> Self {
+        ProfilingWriter { next, counter, profile: ByteProfile::default() }
+    }
+
+    /// Returns the measured bytes per element kind.
+    pub fn profile(&self) -> ByteProfile {
+        self.profile
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for ProfilingWriter<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.profile = ByteProfile::default();
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        let start = self.counter.get();
+        let result = self.next.visit_header();
+        self.profile.add(Bucket::Header, self.counter.get() - start);
+        result
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        let start = self.counter.get();
+        let result = self.next.visit_namespaces(src_namespace, dst_namespaces);
+        self.profile.add(Bucket::Header, self.counter.get() - start);
+        result
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        let start = self.counter.get();
+        let result = self.next.visit_metadata(key, value);
+        self.profile.add(Bucket::Header, self.counter.get() - start);
+        result
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        let start = self.counter.get();
+        let result = self.next.visit_content();
+        self.profile.add(Bucket::Header, self.counter.get() - start);
+        result
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        let start = self.counter.get();
+        let result = self.next.visit_class(src_name);
+        self.profile.add(Bucket::Element(MappedElementKind::Class), self.counter.get() - start);
+        result
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let start = self.counter.get();
+        let result = self.next.visit_field(src_name, src_desc);
+        self.profile.add(Bucket::Element(MappedElementKind::Field), self.counter.get() - start);
+        result
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let start = self.counter.get();
+        let result = self.next.visit_method(src_name, src_desc);
+        self.profile.add(Bucket::Element(MappedElementKind::Method), self.counter.get() - start);
+        result
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        let start = self.counter.get();
+        let result = self.next.visit_method_arg(arg_position, lv_index, src_name);
+        self.profile.add(Bucket::Element(MappedElementKind::MethodArg), self.counter.get() - start);
+        result
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        let start = self.counter.get();
+        let result = self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name);
+        self.profile.add(Bucket::Element(MappedElementKind::MethodVar), self.counter.get() - start);
+        result
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        let start = self.counter.get();
+        let result = self.next.visit_end();
+        self.profile.add(Bucket::Other, self.counter.get() - start);
+        result
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        let start = self.counter.get();
+        let result = self.next.visit_dst_name(target_kind, namespace, name);
+        self.profile.add(Bucket::Element(target_kind), self.counter.get() - start);
+        result
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        let start = self.counter.get();
+        let result = self.next.visit_dst_desc(target_kind, namespace, desc);
+        self.profile.add(Bucket::Element(target_kind), self.counter.get() - start);
+        result
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        let start = self.counter.get();
+        let result = self.next.visit_element_content(target_kind);
+        self.profile.add(Bucket::Element(target_kind), self.counter.get() - start);
+        result
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        let start = self.counter.get();
+        let result = self.next.visit_comment(target_kind, comment);
+        self.profile.add(Bucket::Comment, self.counter.get() - start);
+        result
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that reports the number of visited classes to a callback.
+///
+/// The callback is invoked with the running class count after every `interval` classes,
+/// and once more with the final count in [`visit_end`][MappingVisitor::visit_end]
+/// unless it was just reported. An empty mapping reports a final count of zero.
+/// Only [`visit_class`][MappingVisitor::visit_class] calls are counted. If the producer reports
+/// the number of classes in advance, it is available from [`total`][Self::total].
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::ProgressVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut tree = MappingTree::new();
+/// tree.visit_namespaces("official", &["named"])?;
+/// for name in ["a", "b", "c", "d", "e"] {
+///     tree.visit_class(name)?;
+///     tree.visit_element_content(MappedElementKind::Class)?;
+///     tree.visit_field("f", Some("I"))?;
+///     tree.visit_element_content(MappedElementKind::Field)?;
+/// }
+/// tree.visit_end()?;
+///
+/// let mut counts = Vec::new();
+/// let mut progress = ProgressVisitor::new(MappingTree::new(), 2, |count| counts.push(count));
+/// tree.accept(&mut progress)?;
+/// assert_eq!(progress.total(), Some(5));
+/// drop(progress);
+/// assert_eq!(counts, vec![2, 4, 5]);
+///
+/// // The final count isn't repeated if it's a multiple of the interval.
+/// let mut counts = Vec::new();
+/// tree.accept(&mut ProgressVisitor::new(MappingTree::new(), 5, |count| counts.push(count)))?;
+/// assert_eq!(counts, vec![5]);
+///
+/// // An empty mapping still gets a final report.
+/// let mut empty = MappingTree::new();
+/// empty.visit_namespaces("official", &["named"])?;
+/// let mut counts = Vec::new();
+/// empty.accept(&mut ProgressVisitor::new(MappingTree::new(), 2, |count| counts.push(count)))?;
+/// assert_eq!(counts, vec![0]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ProgressVisitor<V, F> {
+    next: V,
+    interval: usize,
+    callback: F,
+    class_count: usize,
+    /// The last count passed to the callback in this pass.
+    reported: Option<usize>,
+    total: Option<usize>,
+}
+
+impl<V: MappingVisitor, F: FnMut(usize)> ProgressVisitor<V, F> {
+    /// Creates a new progress visitor that calls `callback` every `interval` classes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn new(next: V, interval: usize, callback: F) -> Self {
+        assert!(interval > 0, "Progress interval must be positive");
+        ProgressVisitor { next, interval, callback, class_count: 0, reported: None, total: None }
+    }
+
+    /// Returns the total number of classes, if it was reported in
+    /// [`visit_content_size`][MappingVisitor::visit_content_size].
+    pub fn total(&self) -> Option<usize> {
+        self.total
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor, F: FnMut(usize)> MappingVisitor for ProgressVisitor<V, F> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.class_count = 0;
+        self.reported = None;
+        self.total = None;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.total = Some(classes);
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_count += 1;
+
+        if self.class_count.is_multiple_of(self.interval) {
+            (self.callback)(self.class_count);
+            self.reported = Some(self.class_count);
+        }
+
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        if self.reported != Some(self.class_count) {
+            (self.callback)(self.class_count);
+        }
+
+        self.class_count = 0;
+        self.reported = None;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,170 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use anyhow::Context;
+use crate::MappedElementKind;
+use crate::descriptor::{remap_descriptor, Type};
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that turns ProGuard field types into JVM descriptors in the right namespace.
+///
+/// Source field descriptors that are Java type names (such as `int` or `com.example.Foo[]`) are converted
+/// to JVM descriptors, while valid JVM descriptors are kept as is. Note that this makes single-letter
+/// class names in the default package ambiguous; they are treated as primitive descriptors.
+///
+/// The class references in the resulting descriptors can then be remapped using a map of internal names
+/// set with [`with_renames`](Self::with_renames), for example when the ProGuard types are in a different
+/// namespace than the emitted source descriptors. Method descriptors are passed on unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use jvm_obfuscation_mappings::adapter::ProguardFieldDescVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let renames = HashMap::from([("com/example/Foo".to_owned(), "a".to_owned())]);
+/// let mut visitor = ProguardFieldDescVisitor::new(MappingTree::new()).with_renames(renames);
+/// visitor.visit_namespaces("source", &["target"])?;
+/// visitor.visit_class("b")?;
+/// visitor.visit_field("foo", Some("com.example.Foo[]"))?;
+/// visitor.visit_field("bar", Some("int"))?;
+/// visitor.visit_field("baz", Some("Lcom/example/Foo;"))?;
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// let descs: Vec<_> = tree.class("b").unwrap().fields().iter().map(|field| field.src_desc()).collect();
+/// assert_eq!(descs, [Some("[La;"), Some("I"), Some("La;")]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ProguardFieldDescVisitor<V> {
+    next: V,
+    renames: HashMap<String, String>,
+}
+
+impl<V: MappingVisitor> ProguardFieldDescVisitor<V> {
+    /// Creates a new field descriptor visitor that doesn't remap any classes.
+    pub fn new(next: V) -> Self {
+        ProguardFieldDescVisitor { next, renames: HashMap::new() }
+    }
+
+    /// Sets the map of old internal class names to new ones that is applied to the field descriptors.
+    pub fn with_renames(mut self, renames: HashMap<String, String>) -> Self {
+        self.renames = renames;
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn map_desc(&self, desc: &str) -> VisitResult<String> {
+        let desc = match Type::from_descriptor(desc) {
+            Ok(_) => desc.to_owned(),
+            Err(_) => Type::from_java_name(desc)
+                .with_context(|| format!("Invalid field type {}", desc))?
+                .descriptor(),
+        };
+
+        Ok(remap_descriptor(&desc, |class| self.renames.get(class).cloned()))
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for ProguardFieldDescVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = src_desc.map(|desc| self.map_desc(desc)).transpose()?;
+        self.next.visit_field(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that moves the only destination namespace of its input to another index.
+///
+/// The namespace is renamed to the target name and reported at the target index.
+/// The namespaces before it are reported with the unique placeholder names `_pad0`, `_pad1` and so on,
+/// and never receive any destination names.
+/// Input without exactly one destination namespace results in an error.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::PromoteNamespaceVisitor;
+/// use jvm_obfuscation_mappings::format::srg::SrgReader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let mut visitor = PromoteNamespaceVisitor::new(MappingTree::new(), "srg", 1);
+/// SrgReader::read_str("CL: a com/example/Foo\n", &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// assert_eq!(tree.dst_namespaces(), &["_pad0", "srg"]);
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), None);
+/// assert_eq!(class.dst_name(1), Some("com/example/Foo"));
+///
+/// let mut visitor = PromoteNamespaceVisitor::new(MappingTree::new(), "srg", 2);
+/// SrgReader::read_str("CL: a com/example/Foo\n", &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// assert_eq!(tree.dst_namespaces(), &["_pad0", "_pad1", "srg"]);
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(1), None);
+/// assert_eq!(class.dst_name(2), Some("com/example/Foo"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct PromoteNamespaceVisitor<V> {
+    next: V,
+    target_name: String,
+    target_index: usize,
+}
+
+impl<V: MappingVisitor> PromoteNamespaceVisitor<V> {
+    /// Creates a new namespace promoting visitor that reports the destination namespace
+    /// as `target_name` at `target_index`.
+    pub fn new(next: V, target_name: &str, target_index: usize) -> Self {
+        PromoteNamespaceVisitor { next, target_name: target_name.to_owned(), target_index }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for PromoteNamespaceVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        if dst_namespaces.len() != 1 {
+            bail!("Expected a single destination namespace, found {}", dst_namespaces.len());
+        }
+
+        let padding: Vec<String> = (0..self.target_index).map(|index| format!("_pad{}", index)).collect();
+        let mut promoted: Vec<&str> = padding.iter().map(String::as_str).collect();
+        promoted.push(&self.target_name);
+        self.next.visit_namespaces(src_namespace, &promoted)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, _namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, self.target_index, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, _namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, self.target_index, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,173 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use crate::MappedElementKind;
+use crate::descriptor::{remap_descriptor, CachingRemapper};
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that renames source classes using a map of old internal names to new ones.
+///
+/// The renames are applied to source class names and to class references in source descriptors.
+/// Destination names and descriptors are passed on unchanged.
+///
+/// By default, descriptors are remapped by scanning them for class references.
+/// With [`with_descriptor_cache`][Self::with_descriptor_cache], a [`CachingRemapper`]
+/// is used instead, which validates each distinct descriptor and remaps it only once.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::RenameVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let renames = HashMap::from([("a".to_owned(), "com/example/Foo".to_owned())]);
+/// let mut visitor = RenameVisitor::new(MappingTree::new(), renames).with_descriptor_cache();
+/// visitor.visit_namespaces("official", &["named"])?;
+/// visitor.visit_class("a")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_method("b", Some("(La;)La;"))?;
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// let class = tree.class("com/example/Foo").unwrap();
+/// assert_eq!(class.methods()[0].src_desc(), Some("(Lcom/example/Foo;)Lcom/example/Foo;"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct RenameVisitor<V> {
+    next: V,
+    remapper: CachingRemapper,
+    cache_descriptors: bool,
+}
+
+impl<V: MappingVisitor> RenameVisitor<V> {
+    /// Creates a new rename visitor.
+    pub fn new(next: V, renames: HashMap<String, String>) -> Self {
+        RenameVisitor { next, remapper: CachingRemapper::new(renames), cache_descriptors: false }
+    }
+
+    /// Makes this visitor remap descriptors using a [`CachingRemapper`].
+    pub fn with_descriptor_cache(mut self) -> Self {
+        self.cache_descriptors = true;
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn map_desc(&self, desc: Option<&str>) -> VisitResult<Option<String>> {
+        let desc = match desc {
+            Some(desc) => desc,
+            None => return Ok(None),
+        };
+
+        if self.cache_descriptors {
+            self.remapper.remap(desc).map(Some)
+        } else {
+            Ok(Some(remap_descriptor(desc, |class| self.remapper.map_class(class).map(str::to_owned))))
+        }
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for RenameVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        let src_name = self.remapper.map_class(src_name).unwrap_or(src_name);
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = self.map_desc(src_desc)?;
+        self.next.visit_field(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_desc = self.map_desc(src_desc)?;
+        self.next.visit_method(src_name, src_desc.as_deref())
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,154 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that renames a single namespace.
+///
+/// The namespace can be the source namespace or a destination namespace. The other namespaces
+/// and the namespace indices are left unchanged. Renaming a namespace that doesn't exist results in an error.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::RenameNamespaceVisitor;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tmojang\tnamed\n\
+///             c\ta\tnet/minecraft/Foo\tcom/example/Foo\n";
+/// let mut visitor = RenameNamespaceVisitor::new(MappingTree::new(), "mojang", "mojmap");
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// assert_eq!(tree.dst_namespaces(), &["mojmap", "named"]);
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("net/minecraft/Foo"));
+/// assert_eq!(class.dst_name(1), Some("com/example/Foo"));
+///
+/// let mut visitor = RenameNamespaceVisitor::new(MappingTree::new(), "srg", "searge");
+/// assert!(Tiny2Reader::read_str(tiny, &mut visitor).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct RenameNamespaceVisitor<V> {
+    next: V,
+    old: String,
+    new: String,
+}
+
+impl<V: MappingVisitor> RenameNamespaceVisitor<V> {
+    /// Creates a new namespace renaming visitor that renames the namespace `old` to `new`.
+    pub fn new(next: V, old: &str, new: &str) -> Self {
+        RenameNamespaceVisitor { next, old: old.to_owned(), new: new.to_owned() }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for RenameNamespaceVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        if src_namespace != self.old && !dst_namespaces.contains(&self.old.as_str()) {
+            bail!("Unknown namespace {}", self.old);
+        }
+
+        let new = self.new.as_str();
+        let rename = |namespace| if namespace == self.old { new } else { namespace };
+        let dst_namespaces: Vec<&str> = dst_namespaces.iter().map(|&namespace| rename(namespace)).collect();
+        self.next.visit_namespaces(rename(src_namespace), &dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,170 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that converts between argument positions and local variable indices without descriptors.
+///
+/// Every parameter is assumed to occupy a single local variable slot, so an argument with only
+/// an `arg_position` gets `lv_index = arg_position` (plus one for instance methods, where index 0 holds `this`),
+/// and vice versa. This is only a best-effort conversion: `long` and `double` parameters occupy two slots,
+/// so the indices of all parameters after them are wrong. When the source descriptors are available,
+/// use [`ArgIndexConverter`][super::ArgIndexConverter] instead.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::SimpleArgModelVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut visitor = SimpleArgModelVisitor::assume_single_slot(MappingTree::new());
+/// visitor.visit_namespaces("official", &["named"])?;
+/// visitor.visit_class("a")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_method("b", None)?;
+/// visitor.visit_element_content(MappedElementKind::Method)?;
+///
+/// for position in 0..3 {
+///     visitor.visit_method_arg(position, -1, None)?;
+///     visitor.visit_element_content(MappedElementKind::MethodArg)?;
+/// }
+///
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// let args = tree.class("a").unwrap().methods()[0].args();
+/// let indices: Vec<_> = args.iter().map(|arg| (arg.arg_position(), arg.lv_index())).collect();
+/// assert_eq!(indices, vec![(0, 1), (1, 2), (2, 3)]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct SimpleArgModelVisitor<V> {
+    next: V,
+    is_instance: bool,
+}
+
+impl<V: MappingVisitor> SimpleArgModelVisitor<V> {
+    /// Creates a new visitor that assumes all parameters occupy a single slot and all methods are instance methods.
+    pub fn assume_single_slot(next: V) -> Self {
+        SimpleArgModelVisitor { next, is_instance: true }
+    }
+
+    /// Sets whether methods are assumed to be instance methods, which is `true` by default.
+    pub fn with_is_instance(mut self, is_instance: bool) -> Self {
+        self.is_instance = is_instance;
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for SimpleArgModelVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        let offset = self.is_instance as i32;
+        let mut arg_position = arg_position;
+        let mut lv_index = lv_index;
+
+        if arg_position >= 0 && lv_index < 0 {
+            lv_index = arg_position + offset;
+        } else if lv_index >= offset && arg_position < 0 {
+            arg_position = lv_index - offset;
+        }
+
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,198 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::{bail, Context};
+use crate::MappedElementKind;
+use crate::descriptor::{classify_descriptor, DescriptorKind, MethodDescriptor, Type};
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that checks that the class names in all descriptors are internal names using `/`.
+///
+/// Binary names (`Ljava.lang.String;`) in descriptors are a common mistake that results in unusable mappings,
+/// so any object type containing a `.` results in an error. Descriptors are parsed to find the object types,
+/// so malformed descriptors are rejected as well. Valid descriptors are passed on unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::SlashNameChecker;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut checker = SlashNameChecker::new(MappingTree::new());
+/// checker.visit_namespaces("official", &["named"])?;
+/// checker.visit_class("a")?;
+/// checker.visit_element_content(MappedElementKind::Class)?;
+/// checker.visit_method("b", Some("(Ljava/lang/String;)V"))?;
+/// checker.visit_element_content(MappedElementKind::Method)?;
+///
+/// let error = checker.visit_field("c", Some("Ljava.lang.String;")).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "Descriptor Ljava.lang.String; of Field c uses the binary class name java.lang.String",
+/// );
+///
+/// let error = checker.visit_method("d", Some("([[Ljava.lang.Object;)V")).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "Descriptor ([[Ljava.lang.Object;)V of Method d uses the binary class name java.lang.Object",
+/// );
+/// assert!(checker.visit_method("e", Some("(Ljava/lang/String)V")).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct SlashNameChecker<V> {
+    next: V,
+    member_name: String,
+}
+
+impl<V: MappingVisitor> SlashNameChecker<V> {
+    /// Creates a new slash name checker.
+    pub fn new(next: V) -> Self {
+        SlashNameChecker { next, member_name: String::new() }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+/// Parses a field or method descriptor into the types it contains.
+fn desc_types(desc: &str) -> anyhow::Result<Vec<Type>> {
+    Ok(match classify_descriptor(desc) {
+        DescriptorKind::Field => vec![Type::from_descriptor(desc)?],
+        DescriptorKind::Method => {
+            let method = MethodDescriptor::parse(desc)?;
+            method.params().iter().chain(std::iter::once(method.return_type())).cloned().collect()
+        }
+    })
+}
+
+/// Checks that no object type in the descriptor of a member contains a `.`.
+fn check_desc(kind: MappedElementKind, member_name: &str, desc: &str) -> VisitResult<()> {
+    let types = desc_types(desc).with_context(|| format!("Invalid descriptor {} of {:?} {}", desc, kind, member_name))?;
+
+    for mut ty in &types {
+        while let Type::Array(element) = ty {
+            ty = element;
+        }
+
+        if let Type::Object(class) = ty {
+            if class.internal_name().contains('.') {
+                bail!("Descriptor {} of {:?} {} uses the binary class name {}", desc, kind, member_name, class.internal_name());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl<V: MappingVisitor> MappingVisitor for SlashNameChecker<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member_name = src_name.to_owned();
+
+        if let Some(src_desc) = src_desc {
+            check_desc(MappedElementKind::Field, src_name, src_desc)?;
+        }
+
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member_name = src_name.to_owned();
+
+        if let Some(src_desc) = src_desc {
+            check_desc(MappedElementKind::Method, src_name, src_desc)?;
+        }
+
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        check_desc(target_kind, &self.member_name, desc)?;
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,195 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that annotates the comment of each element with the element's source name.
+///
+/// By default, the annotation `(was <source name>)` is prepended to existing comments.
+/// Elements without comments get a comment consisting of just the annotation.
+/// Method arguments and local variables without source names are left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::SourceAnnotatingCommentVisitor;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tFoo\n\
+///             c\tb\tBar\n\
+///             \tc\tThe bar.\n";
+/// let mut visitor = SourceAnnotatingCommentVisitor::new(MappingTree::new());
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// assert_eq!(tree.class("a").unwrap().comment(), Some("(was a)"));
+/// assert_eq!(tree.class("b").unwrap().comment(), Some("(was b) The bar."));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct SourceAnnotatingCommentVisitor<V> {
+    next: V,
+    append: bool,
+    src_name: Option<String>,
+    pending: Option<(MappedElementKind, String)>,
+}
+
+impl<V: MappingVisitor> SourceAnnotatingCommentVisitor<V> {
+    /// Creates a new source annotating comment visitor.
+    pub fn new(next: V) -> Self {
+        SourceAnnotatingCommentVisitor { next, append: false, src_name: None, pending: None }
+    }
+
+    /// Sets whether the annotation is appended to existing comments on a new line instead of being prepended.
+    pub fn with_append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    /// Synthesizes a comment for the last element if it didn't receive one.
+    fn flush_comment(&mut self) -> VisitResult<()> {
+        if let Some((kind, src_name)) = self.pending.take() {
+            self.next.visit_comment(kind, &format!("(was {})", src_name))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for SourceAnnotatingCommentVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.src_name = None;
+        self.pending = None;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.flush_comment()?;
+        self.src_name = Some(src_name.to_owned());
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.flush_comment()?;
+        self.src_name = Some(src_name.to_owned());
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.flush_comment()?;
+        self.src_name = Some(src_name.to_owned());
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.flush_comment()?;
+        self.src_name = src_name.map(str::to_owned);
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.flush_comment()?;
+        self.src_name = src_name.map(str::to_owned);
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.flush_comment()?;
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        let visit_content = self.next.visit_element_content(target_kind)?;
+
+        if visit_content {
+            self.pending = self.src_name.take().map(|src_name| (target_kind, src_name));
+        }
+
+        Ok(visit_content)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        match self.pending.take() {
+            Some((kind, src_name)) if kind == target_kind => {
+                let annotation = format!("(was {})", src_name);
+
+                let comment = if self.append {
+                    format!("{}\n{}", comment, annotation)
+                } else {
+                    format!("{} {}", annotation, comment)
+                };
+
+                self.next.visit_comment(target_kind, &comment)
+            }
+            pending => {
+                self.pending = pending;
+                self.next.visit_comment(target_kind, comment)
+            }
+        }
+    }
+}
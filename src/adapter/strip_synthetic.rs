@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The name patterns of synthetic classes skipped by [`StripSyntheticVisitor::new`].
+pub const DEFAULT_SYNTHETIC_PATTERNS: &[&str] = &["$$Lambda$", "$$ExternalSyntheticLambda"];
+
+/// A visitor that skips synthetic classes, such as the classes generated for lambdas.
+///
+/// A class is skipped if its source name contains any of the patterns,
+/// which are [`DEFAULT_SYNTHETIC_PATTERNS`] by default. Other classes are passed on unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::StripSyntheticVisitor;
+/// use jvm_obfuscation_mappings::format::srg::SrgReader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let srg = "CL: a com/example/Foo\n\
+///            CL: a$$Lambda$1 com/example/Foo$$Lambda$1\n\
+///            CL: a$Gen com/example/Foo$Gen\n";
+/// let mut visitor = StripSyntheticVisitor::new(MappingTree::new());
+/// SrgReader::read_str(srg, &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// assert!(tree.class("a").is_some());
+/// assert!(tree.class("a$$Lambda$1").is_none());
+/// assert!(tree.class("a$Gen").is_some());
+///
+/// let mut visitor = StripSyntheticVisitor::with_patterns(MappingTree::new(), &["$Gen"]);
+/// SrgReader::read_str(srg, &mut visitor)?;
+///
+/// let tree = visitor.into_inner();
+/// assert!(tree.class("a$$Lambda$1").is_some());
+/// assert!(tree.class("a$Gen").is_none());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct StripSyntheticVisitor<V> {
+    next: V,
+    patterns: Vec<String>,
+}
+
+impl<V: MappingVisitor> StripSyntheticVisitor<V> {
+    /// Creates a new visitor that skips classes matching [`DEFAULT_SYNTHETIC_PATTERNS`].
+    pub fn new(next: V) -> Self {
+        StripSyntheticVisitor::with_patterns(next, DEFAULT_SYNTHETIC_PATTERNS)
+    }
+
+    /// Creates a new visitor that skips classes whose source names contain any of `patterns`.
+    pub fn with_patterns(next: V, patterns: &[&str]) -> Self {
+        StripSyntheticVisitor { next, patterns: patterns.iter().map(|&pattern| pattern.to_owned()).collect() }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for StripSyntheticVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        if self.patterns.iter().any(|pattern| src_name.contains(pattern.as_str())) {
+            return Ok(false);
+        }
+
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,159 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that appends a suffix to all destination class names.
+///
+/// The suffix is added to the simple name of the class, which for nested classes
+/// is the innermost `$`-separated segment. The package and the outer class names are kept as they are.
+/// Empty class names mark classes without a name in a namespace, so they are passed on unchanged.
+///
+/// Destination descriptors are passed through unchanged, since they can refer to classes
+/// that aren't part of the mappings. Visitors that need destination descriptors should be fed
+/// through a [`MappingTree`](crate::tree::MappingTree), which computes them from the suffixed class names.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::SuffixClassNamesVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut visitor = SuffixClassNamesVisitor::new(MappingTree::new(), "X");
+/// visitor.visit_namespaces("official", &["named", "intermediary"])?;
+/// visitor.visit_class("a")?;
+/// visitor.visit_dst_name(MappedElementKind::Class, 0, "com/a/B$C")?;
+/// visitor.visit_dst_name(MappedElementKind::Class, 1, "")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_field("b", Some("I"))?;
+/// visitor.visit_dst_name(MappedElementKind::Field, 0, "count")?;
+/// visitor.visit_element_content(MappedElementKind::Field)?;
+/// visitor.visit_end()?;
+///
+/// let tree = visitor.into_inner();
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("com/a/B$CX"));
+/// assert_eq!(class.dst_name(1), Some(""));
+/// assert_eq!(class.field("b", Some("I")).unwrap().dst_name(0), Some("count"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct SuffixClassNamesVisitor<V> {
+    next: V,
+    suffix: String,
+}
+
+impl<V: MappingVisitor> SuffixClassNamesVisitor<V> {
+    /// Creates a new class name suffixing visitor that appends `suffix` to destination class names.
+    pub fn new(next: V, suffix: &str) -> Self {
+        SuffixClassNamesVisitor { next, suffix: suffix.to_owned() }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for SuffixClassNamesVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Class && !name.is_empty() {
+            let name = format!("{}{}", name, self.suffix);
+            return self.next.visit_dst_name(target_kind, namespace, &name);
+        }
+
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
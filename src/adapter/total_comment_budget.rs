@@ -0,0 +1,185 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that limits the total size of all comments in a mapping.
+///
+/// Comments are passed on while their cumulative length in bytes stays within the budget.
+/// Once a comment doesn't fit, it and all further comments are dropped, or an error is returned
+/// if [`with_error_on_exceed`][Self::with_error_on_exceed] is set. Names and the structure
+/// of the mapping are always passed on unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::TotalCommentBudgetVisitor;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tc\tThe foo.\n\
+///             \tf\tI\tb\tcount\n\
+///             \t\tc\tThe number of foos.\n\
+///             c\tc\tcom/example/Bar\n\
+///             \tc\tBar.\n";
+/// let mut visitor = TotalCommentBudgetVisitor::new(MappingTree::new(), 16);
+/// Tiny2Reader::read_str(tiny, &mut visitor)?;
+/// assert_eq!(visitor.dropped_comments(), 2);
+///
+/// let tree = visitor.into_inner();
+/// let foo = tree.class("a").unwrap();
+/// assert_eq!(foo.comment(), Some("The foo."));
+/// assert_eq!(foo.fields()[0].comment(), None);
+/// assert_eq!(foo.fields()[0].dst_name(0), Some("count"));
+/// assert_eq!(tree.class("c").unwrap().comment(), None);
+/// assert_eq!(tree.class("c").unwrap().dst_name(0), Some("com/example/Bar"));
+///
+/// let mut visitor = TotalCommentBudgetVisitor::new(MappingTree::new(), 16).with_error_on_exceed(true);
+/// assert!(Tiny2Reader::read_str(tiny, &mut visitor).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct TotalCommentBudgetVisitor<V> {
+    next: V,
+    budget: usize,
+    error_on_exceed: bool,
+    used: usize,
+    exhausted: bool,
+    dropped: usize,
+}
+
+impl<V: MappingVisitor> TotalCommentBudgetVisitor<V> {
+    /// Creates a new visitor that passes on at most `budget` bytes of comments.
+    pub fn new(next: V, budget: usize) -> Self {
+        TotalCommentBudgetVisitor { next, budget, error_on_exceed: false, used: 0, exhausted: false, dropped: 0 }
+    }
+
+    /// Sets whether exceeding the budget results in an error instead of dropping the comments.
+    pub fn with_error_on_exceed(mut self, error_on_exceed: bool) -> Self {
+        self.error_on_exceed = error_on_exceed;
+        self
+    }
+
+    /// Returns the number of comments dropped because they didn't fit in the budget.
+    pub fn dropped_comments(&self) -> usize {
+        self.dropped
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for TotalCommentBudgetVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.used = 0;
+        self.exhausted = false;
+        self.dropped = 0;
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        if !self.exhausted && self.used + comment.len() <= self.budget {
+            self.used += comment.len();
+            return self.next.visit_comment(target_kind, comment);
+        }
+
+        if self.error_on_exceed {
+            bail!("Comments exceed the budget of {} bytes", self.budget);
+        }
+
+        self.exhausted = true;
+        self.dropped += 1;
+        Ok(())
+    }
+}
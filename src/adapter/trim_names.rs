@@ -0,0 +1,169 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use anyhow::bail;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that removes leading and trailing ASCII whitespace from source and destination names.
+///
+/// Descriptors, comments and metadata are passed on unchanged. If the visitor is configured to
+/// [error on changes](Self::with_error_on_change), names that would be changed by trimming
+/// result in an error instead, which helps finding bugs in readers.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::adapter::TrimNamesVisitor;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut visitor = TrimNamesVisitor::new(MappingTree::new());
+/// visitor.visit_namespaces("official", &["named"])?;
+/// visitor.visit_class("a")?;
+/// visitor.visit_dst_name(MappedElementKind::Class, 0, "  foo ")?;
+/// visitor.visit_element_content(MappedElementKind::Class)?;
+/// visitor.visit_end()?;
+/// assert_eq!(visitor.into_inner().class("a").unwrap().dst_name(0), Some("foo"));
+///
+/// let mut visitor = TrimNamesVisitor::new(MappingTree::new()).with_error_on_change(true);
+/// visitor.visit_namespaces("official", &["named"])?;
+/// let error = visitor.visit_class("  foo ").unwrap_err();
+/// assert_eq!(error.to_string(), "Name \"  foo \" has surrounding whitespace");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct TrimNamesVisitor<V> {
+    next: V,
+    error_on_change: bool,
+}
+
+impl<V: MappingVisitor> TrimNamesVisitor<V> {
+    /// Creates a new name trimming visitor.
+    pub fn new(next: V) -> Self {
+        TrimNamesVisitor { next, error_on_change: false }
+    }
+
+    /// Sets whether names with surrounding whitespace result in an error instead of being trimmed.
+    pub fn with_error_on_change(mut self, error_on_change: bool) -> Self {
+        self.error_on_change = error_on_change;
+        self
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    fn trim<'a>(&self, name: &'a str) -> VisitResult<&'a str> {
+        let trimmed = name.trim_matches(|c: char| c.is_ascii_whitespace());
+
+        if self.error_on_change && trimmed.len() != name.len() {
+            bail!("Name {:?} has surrounding whitespace", name);
+        }
+
+        Ok(trimmed)
+    }
+}
+
+impl<V: MappingVisitor> MappingVisitor for TrimNamesVisitor<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        self.next.flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.next.visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.next.visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.next.visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.next.visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.next.visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        let src_name = self.trim(src_name)?;
+        self.next.visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_name = self.trim(src_name)?;
+        self.next.visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        let src_name = self.trim(src_name)?;
+        self.next.visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        let src_name = src_name.map(|name| self.trim(name)).transpose()?;
+        self.next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        let src_name = src_name.map(|name| self.trim(name)).transpose()?;
+        self.next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.next.visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        let name = self.trim(name)?;
+        self.next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.next.visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.next.visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,322 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The collection and rewrite logic of a [`TwoPassVisitor`].
+///
+/// The `collect_*` methods are called in the first pass, which isn't passed on to the wrapped visitor.
+/// By default, the first pass only visits the classes and skips everything else.
+///
+/// The remaining methods are called in the second pass with the wrapped visitor as `next`.
+/// By default, they forward the visit to `next` unchanged.
+pub trait TwoPassAdapter {
+    /// Clears all state collected in the first pass.
+    fn reset(&mut self) {}
+
+    /// Determines whether the header should be visited in the first pass.
+    fn collect_header(&mut self) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    /// Collects the namespaces in the first pass.
+    fn collect_namespaces(&mut self, _src_namespace: &str, _dst_namespaces: &[&str]) -> VisitResult<()> {
+        Ok(())
+    }
+
+    /// Collects a class in the first pass and determines whether the rest of it should be visited.
+    fn collect_class(&mut self, _src_name: &str) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    /// Collects a field in the first pass and determines whether the rest of it should be visited.
+    fn collect_field(&mut self, _src_name: &str, _src_desc: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    /// Collects a method in the first pass and determines whether the rest of it should be visited.
+    fn collect_method(&mut self, _src_name: &str, _src_desc: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    /// Collects a destination name in the first pass.
+    fn collect_dst_name(&mut self, _target_kind: MappedElementKind, _namespace: usize, _name: &str) -> VisitResult<()> {
+        Ok(())
+    }
+
+    /// Determines whether the element content should be visited in the first pass.
+    ///
+    /// By default, only the members of classes are visited.
+    fn collect_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        Ok(target_kind == MappedElementKind::Class)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_header`] in the second pass.
+    fn visit_header<V: MappingVisitor>(&mut self, next: &mut V) -> VisitResult<bool> {
+        next.visit_header()
+    }
+
+    /// Rewrites [`MappingVisitor::visit_namespaces`] in the second pass.
+    fn visit_namespaces<V: MappingVisitor>(&mut self, next: &mut V, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        next.visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_metadata`] in the second pass.
+    fn visit_metadata<V: MappingVisitor>(&mut self, next: &mut V, key: &str, value: &str) -> VisitResult<()> {
+        next.visit_metadata(key, value)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_content`] in the second pass.
+    fn visit_content<V: MappingVisitor>(&mut self, next: &mut V) -> VisitResult<bool> {
+        next.visit_content()
+    }
+
+    /// Rewrites [`MappingVisitor::visit_content_size`] in the second pass.
+    fn visit_content_size<V: MappingVisitor>(&mut self, next: &mut V, classes: usize) -> VisitResult<()> {
+        next.visit_content_size(classes)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_source_position`] in the second pass.
+    fn visit_source_position<V: MappingVisitor>(&mut self, next: &mut V, line: usize) -> VisitResult<()> {
+        next.visit_source_position(line)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_class`] in the second pass.
+    fn visit_class<V: MappingVisitor>(&mut self, next: &mut V, src_name: &str) -> VisitResult<bool> {
+        next.visit_class(src_name)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_field`] in the second pass.
+    fn visit_field<V: MappingVisitor>(&mut self, next: &mut V, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        next.visit_field(src_name, src_desc)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_method`] in the second pass.
+    fn visit_method<V: MappingVisitor>(&mut self, next: &mut V, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        next.visit_method(src_name, src_desc)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_method_arg`] in the second pass.
+    fn visit_method_arg<V: MappingVisitor>(&mut self, next: &mut V, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        next.visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_method_var`] in the second pass.
+    fn visit_method_var<V: MappingVisitor>(&mut self, next: &mut V, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        next.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_end`] in the second pass.
+    fn visit_end<V: MappingVisitor>(&mut self, next: &mut V) -> VisitResult<bool> {
+        next.visit_end()
+    }
+
+    /// Rewrites [`MappingVisitor::visit_dst_name`] in the second pass.
+    fn visit_dst_name<V: MappingVisitor>(&mut self, next: &mut V, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        next.visit_dst_name(target_kind, namespace, name)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_dst_desc`] in the second pass.
+    fn visit_dst_desc<V: MappingVisitor>(&mut self, next: &mut V, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        next.visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_element_content`] in the second pass.
+    fn visit_element_content<V: MappingVisitor>(&mut self, next: &mut V, target_kind: MappedElementKind) -> VisitResult<bool> {
+        next.visit_element_content(target_kind)
+    }
+
+    /// Rewrites [`MappingVisitor::visit_comment`] in the second pass.
+    fn visit_comment<V: MappingVisitor>(&mut self, next: &mut V, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        next.visit_comment(target_kind, comment)
+    }
+}
+
+/// A visitor that first collects information about its input with a [`TwoPassAdapter`],
+/// and then passes the input on rewritten by the same adapter.
+///
+/// The first pass isn't passed on to the wrapped visitor, which only sees the second pass.
+/// This visitor always [needs multiple passes](MappingFlag::NeedsMultiplePasses).
+pub struct TwoPassVisitor<A, V> {
+    next: V,
+    adapter: A,
+    collecting: bool,
+}
+
+impl<A: TwoPassAdapter, V: MappingVisitor> TwoPassVisitor<A, V> {
+    /// Creates a new two-pass visitor that collects and rewrites with `adapter`.
+    pub fn with_adapter(next: V, adapter: A) -> Self {
+        TwoPassVisitor { next, adapter, collecting: true }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+}
+
+impl<A: TwoPassAdapter, V: MappingVisitor> MappingVisitor for TwoPassVisitor<A, V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        let mut flags = self.next.flags();
+        flags.insert(MappingFlag::NeedsMultiplePasses);
+        flags
+    }
+
+    fn supports_comments(&self) -> bool {
+        self.next.supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        self.next.supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        self.collecting = true;
+        self.adapter.reset();
+        self.next.reset();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        if self.collecting {
+            return self.adapter.collect_header();
+        }
+
+        self.adapter.visit_header(&mut self.next)
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        if self.collecting {
+            return self.adapter.collect_namespaces(src_namespace, dst_namespaces);
+        }
+
+        self.adapter.visit_namespaces(&mut self.next, src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        if self.collecting {
+            return Ok(());
+        }
+
+        self.adapter.visit_metadata(&mut self.next, key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        if self.collecting {
+            return Ok(true);
+        }
+
+        self.adapter.visit_content(&mut self.next)
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        if self.collecting {
+            return Ok(());
+        }
+
+        self.adapter.visit_content_size(&mut self.next, classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        if self.collecting {
+            return Ok(());
+        }
+
+        self.adapter.visit_source_position(&mut self.next, line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        if self.collecting {
+            return self.adapter.collect_class(src_name);
+        }
+
+        self.adapter.visit_class(&mut self.next, src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if self.collecting {
+            return self.adapter.collect_field(src_name, src_desc);
+        }
+
+        self.adapter.visit_field(&mut self.next, src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        if self.collecting {
+            return self.adapter.collect_method(src_name, src_desc);
+        }
+
+        self.adapter.visit_method(&mut self.next, src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        if self.collecting {
+            return Ok(false);
+        }
+
+        self.adapter.visit_method_arg(&mut self.next, arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        if self.collecting {
+            return Ok(false);
+        }
+
+        self.adapter.visit_method_var(&mut self.next, lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        if self.collecting {
+            self.collecting = false;
+            return Ok(false);
+        }
+
+        self.adapter.visit_end(&mut self.next)
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if self.collecting {
+            return self.adapter.collect_dst_name(target_kind, namespace, name);
+        }
+
+        self.adapter.visit_dst_name(&mut self.next, target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        if self.collecting {
+            return Ok(());
+        }
+
+        self.adapter.visit_dst_desc(&mut self.next, target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        if self.collecting {
+            return self.adapter.collect_element_content(target_kind);
+        }
+
+        self.adapter.visit_element_content(&mut self.next, target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        if self.collecting {
+            return Ok(());
+        }
+
+        self.adapter.visit_comment(&mut self.next, target_kind, comment)
+    }
+}
@@ -14,7 +14,9 @@
  * limitations under the License.
 */
 
+use std::borrow::Cow;
 use std::fmt;
+use anyhow::bail;
 use crate::descriptor::Type;
 
 /// A JVM class name.
@@ -81,6 +83,30 @@ impl ClassName {
         ClassName { internal_name, }
     }
 
+    /// Creates a class name from an object type descriptor (`L<internal name>;`).
+    ///
+    /// Primitive and array descriptors aren't accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::ClassName;
+    ///
+    /// let name = ClassName::from_descriptor("Ljava/lang/String;")?;
+    /// assert_eq!(name.internal_name(), "java/lang/String");
+    /// assert!(ClassName::from_descriptor("I").is_err());
+    /// assert!(ClassName::from_descriptor("[Ljava/lang/String;").is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_descriptor(desc: &str) -> anyhow::Result<ClassName> {
+        match desc.strip_prefix('L').and_then(|rest| rest.strip_suffix(';')) {
+            Some(internal_name) if !internal_name.is_empty() && !internal_name.contains(';') => {
+                Ok(ClassName::from_internal_name(internal_name))
+            }
+            _ => bail!("Not an object type descriptor: {}", desc),
+        }
+    }
+
     /// Returns the internal name of this class name.
     ///
     /// # Examples
@@ -109,6 +135,51 @@ impl ClassName {
         self.internal_name.replace('/', ".")
     }
 
+    /// Returns the binary name of this class name, borrowing the internal name if they're equal.
+    ///
+    /// Classes in the default package don't contain any `/` characters, so their binary names
+    /// can be returned without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use jvm_obfuscation_mappings::descriptor::ClassName;
+    ///
+    /// let simple = ClassName::from_internal_name("Foo");
+    /// assert!(matches!(simple.binary_name_cow(), Cow::Borrowed("Foo")));
+    ///
+    /// let packaged = ClassName::from_internal_name("java/lang/String");
+    /// assert!(matches!(packaged.binary_name_cow(), Cow::Owned(name) if name == "java.lang.String"));
+    /// ```
+    pub fn binary_name_cow(&self) -> Cow<'_, str> {
+        if self.internal_name.contains('/') {
+            Cow::Owned(self.binary_name())
+        } else {
+            Cow::Borrowed(&self.internal_name)
+        }
+    }
+
+    /// Returns the simple name of this class name, which is the internal name without the package.
+    ///
+    /// Nested classes keep the names of their outer classes, separated using `$`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::ClassName;
+    ///
+    /// assert_eq!(ClassName::from_internal_name("java/lang/String").simple_name(), "String");
+    /// assert_eq!(ClassName::from_internal_name("java/util/Map$Entry").simple_name(), "Map$Entry");
+    /// assert_eq!(ClassName::from_internal_name("Foo").simple_name(), "Foo");
+    /// ```
+    pub fn simple_name(&self) -> &str {
+        match self.internal_name.rfind('/') {
+            Some(index) => &self.internal_name[index + 1..],
+            None => &self.internal_name,
+        }
+    }
+
     /// Returns a [`Type`] representing an object type with this class name.
     ///
     /// # Examples
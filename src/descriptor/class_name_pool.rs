@@ -0,0 +1,89 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashMap;
+use crate::descriptor::ClassName;
+
+/// A cheap handle to a [`ClassName`] interned in a [`ClassNamePool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ClassHandle(usize);
+
+/// A pool that stores each distinct [`ClassName`] once and refers to it using [`ClassHandle`]s.
+///
+/// Handles are only meaningful for the pool that created them.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::{ClassName, ClassNamePool};
+///
+/// let mut pool = ClassNamePool::new();
+/// let string = pool.intern(ClassName::from_internal_name("java/lang/String"));
+/// let object = pool.intern(ClassName::from_internal_name("java/lang/Object"));
+///
+/// assert_eq!(pool.intern(ClassName::from_binary_name("java.lang.String")), string);
+/// assert_ne!(string, object);
+/// assert_eq!(pool.resolve(string).internal_name(), "java/lang/String");
+/// assert_eq!(pool.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ClassNamePool {
+    names: Vec<ClassName>,
+    handles: HashMap<ClassName, ClassHandle>,
+}
+
+impl ClassNamePool {
+    /// Creates a new, empty pool.
+    pub fn new() -> ClassNamePool {
+        ClassNamePool::default()
+    }
+
+    /// Interns a class name, returning the existing handle if an equal name is already in the pool.
+    pub fn intern(&mut self, name: ClassName) -> ClassHandle {
+        if let Some(&handle) = self.handles.get(&name) {
+            return handle;
+        }
+
+        let handle = ClassHandle(self.names.len());
+        self.names.push(name.clone());
+        self.handles.insert(name, handle);
+        handle
+    }
+
+    /// Returns the handle of a class name if it's in the pool.
+    pub fn get(&self, name: &ClassName) -> Option<ClassHandle> {
+        self.handles.get(name).copied()
+    }
+
+    /// Returns the class name of a handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handle was created by another pool and is out of bounds for this one.
+    pub fn resolve(&self, handle: ClassHandle) -> &ClassName {
+        &self.names[handle.0]
+    }
+
+    /// Returns the number of distinct class names in the pool.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns true if the pool is empty.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
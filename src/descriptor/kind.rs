@@ -0,0 +1,103 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use crate::descriptor::{MethodDescriptor, Type};
+
+/// The kind of element that a descriptor describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DescriptorKind {
+    /// A field descriptor, such as `I` or `Ljava/lang/String;`.
+    Field,
+    /// A method descriptor, such as `(I)V`.
+    Method,
+}
+
+/// Classifies a descriptor as a field or method descriptor without validating it.
+///
+/// Descriptors starting with `(` are method descriptors, and all others are field descriptors.
+/// Use [`classify_descriptor_strict`] to also check that the descriptor is valid.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::{classify_descriptor, DescriptorKind};
+///
+/// assert_eq!(classify_descriptor("(I)V"), DescriptorKind::Method);
+/// assert_eq!(classify_descriptor("Ljava/lang/String;"), DescriptorKind::Field);
+/// ```
+pub fn classify_descriptor(desc: &str) -> DescriptorKind {
+    if desc.starts_with('(') {
+        DescriptorKind::Method
+    } else {
+        DescriptorKind::Field
+    }
+}
+
+/// Classifies a descriptor as a field or method descriptor, and parses it to check that it's valid.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::{classify_descriptor_strict, DescriptorKind};
+///
+/// assert_eq!(classify_descriptor_strict("(I)V")?, DescriptorKind::Method);
+/// assert_eq!(classify_descriptor_strict("Ljava/lang/String;")?, DescriptorKind::Field);
+/// assert!(classify_descriptor_strict("(I").is_err());
+/// assert!(classify_descriptor_strict("Ljava/lang/String").is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn classify_descriptor_strict(desc: &str) -> anyhow::Result<DescriptorKind> {
+    let kind = classify_descriptor(desc);
+
+    match kind {
+        DescriptorKind::Field => {
+            Type::from_descriptor(desc)?;
+        }
+        DescriptorKind::Method => {
+            MethodDescriptor::parse(desc)?;
+        }
+    }
+
+    Ok(kind)
+}
+
+/// Checks whether two field or method descriptors describe the same type by comparing their parsed forms.
+///
+/// A field descriptor is never equivalent to a method descriptor.
+/// Malformed descriptors result in an error instead of being compared as strings.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::descriptors_equivalent;
+///
+/// assert!(descriptors_equivalent("(ILjava/lang/String;)V", "(ILjava/lang/String;)V")?);
+/// assert!(!descriptors_equivalent("(ILjava/lang/String;)V", "(JLjava/lang/String;)V")?);
+/// assert!(!descriptors_equivalent("I", "()I")?);
+/// assert!(descriptors_equivalent("I", "Ljava/lang/String").is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn descriptors_equivalent(a: &str, b: &str) -> anyhow::Result<bool> {
+    Ok(match (classify_descriptor(a), classify_descriptor(b)) {
+        (DescriptorKind::Field, DescriptorKind::Field) => Type::from_descriptor(a)? == Type::from_descriptor(b)?,
+        (DescriptorKind::Method, DescriptorKind::Method) => MethodDescriptor::parse(a)? == MethodDescriptor::parse(b)?,
+        _ => {
+            classify_descriptor_strict(a)?;
+            classify_descriptor_strict(b)?;
+            false
+        }
+    })
+}
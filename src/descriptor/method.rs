@@ -0,0 +1,352 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::fmt;
+use anyhow::bail;
+use crate::descriptor::Type;
+use crate::descriptor::types::{parse_return_type, parse_type};
+
+/// A JVM method descriptor, consisting of parameter types and a return type.
+///
+/// # Displaying method descriptors
+///
+/// The [`Display`][std::fmt::Display] implementation outputs the bytecode descriptor.
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::{MethodDescriptor, Type};
+///
+/// let desc = MethodDescriptor::new(vec![Type::Int, Type::Boolean], Type::Void);
+/// assert_eq!(desc.to_string(), String::from("(IZ)V"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MethodDescriptor {
+    params: Vec<Type>,
+    return_type: Type,
+}
+
+impl MethodDescriptor {
+    /// Creates a method descriptor from owned parameter types and a return type.
+    pub fn new(params: Vec<Type>, return_type: Type) -> MethodDescriptor {
+        MethodDescriptor { params, return_type, }
+    }
+
+    /// Creates a method descriptor from borrowed parameter types and a return type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{MethodDescriptor, Type};
+    ///
+    /// let desc = MethodDescriptor::from_parts(&[Type::Int], &Type::Void);
+    /// assert_eq!(&desc.descriptor(), "(I)V");
+    /// ```
+    pub fn from_parts(params: &[Type], return_type: &Type) -> MethodDescriptor {
+        MethodDescriptor::new(params.to_vec(), return_type.clone())
+    }
+
+    /// Parses a method descriptor from its JVM bytecode form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{MethodDescriptor, Type};
+    ///
+    /// let desc = MethodDescriptor::parse("(IJ)Z")?;
+    /// assert_eq!(desc.params(), &[Type::Int, Type::Long]);
+    /// assert_eq!(desc.return_type(), &Type::Boolean);
+    /// assert!(MethodDescriptor::parse("(I").is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn parse(desc: &str) -> anyhow::Result<MethodDescriptor> {
+        let mut rest = match desc.strip_prefix('(') {
+            Some(rest) => rest,
+            None => bail!("Method descriptor {} doesn't start with (", desc),
+        };
+        let mut params = Vec::new();
+
+        while !rest.starts_with(')') {
+            if rest.is_empty() {
+                bail!("Unterminated parameter list in method descriptor {}", desc);
+            }
+
+            let (param, param_rest) = parse_type(rest)?;
+            params.push(param);
+            rest = param_rest;
+        }
+
+        let (return_type, rest) = parse_return_type(&rest[1..])?;
+
+        if !rest.is_empty() {
+            bail!("Trailing characters {} in method descriptor {}", rest, desc);
+        }
+
+        Ok(MethodDescriptor { params, return_type, })
+    }
+
+    /// Returns the parameter types of this method descriptor.
+    pub fn params(&self) -> &[Type] {
+        &self.params
+    }
+
+    /// Returns the return type of this method descriptor.
+    pub fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    /// Returns the local variable indices of the parameters of a method with this descriptor.
+    ///
+    /// Instance methods start at index 1, as index 0 holds `this`. Parameters for which
+    /// [`Type::occupies_two_slots`] is true take up two indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{MethodDescriptor, Type};
+    ///
+    /// let desc = MethodDescriptor::new(vec![Type::Long, Type::Int, Type::Double], Type::Void);
+    /// assert_eq!(desc.arg_lv_indices(true), vec![0, 2, 3]);
+    /// assert_eq!(desc.arg_lv_indices(false), vec![1, 3, 4]);
+    /// ```
+    pub fn arg_lv_indices(&self, is_static: bool) -> Vec<usize> {
+        let mut lv_index = if is_static { 0 } else { 1 };
+
+        self.params.iter()
+            .map(|param| {
+                let index = lv_index;
+                lv_index += if param.occupies_two_slots() { 2 } else { 1 };
+                index
+            })
+            .collect()
+    }
+
+    /// Returns a copy of this method descriptor with the return type replaced by `f` applied to it.
+    ///
+    /// The parameter types are kept unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{ClassName, MethodDescriptor};
+    ///
+    /// let desc = MethodDescriptor::parse("(I)Ljava/lang/String;")?;
+    /// let mapped = desc.map_return(|_| ClassName::from_internal_name("java/lang/CharSequence").to_type());
+    /// assert_eq!(&mapped.descriptor(), "(I)Ljava/lang/CharSequence;");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn map_return(&self, f: impl FnOnce(&Type) -> Type) -> MethodDescriptor {
+        MethodDescriptor::new(self.params.clone(), f(&self.return_type))
+    }
+
+    /// Returns a copy of this method descriptor with each parameter type replaced by `f` applied to it.
+    ///
+    /// The return type is kept unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{MethodDescriptor, Type};
+    ///
+    /// let desc = MethodDescriptor::parse("(IJ)I")?;
+    /// let mapped = desc.map_params(|param| if *param == Type::Int { Type::Long } else { param.clone() });
+    /// assert_eq!(&mapped.descriptor(), "(JJ)I");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn map_params(&self, f: impl FnMut(&Type) -> Type) -> MethodDescriptor {
+        MethodDescriptor::new(self.params.iter().map(f).collect(), self.return_type.clone())
+    }
+
+    /// Gets the JVM bytecode descriptor for this method descriptor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{ClassName, MethodDescriptor, Type};
+    ///
+    /// let string = ClassName::from_binary_name("java.lang.String").to_type();
+    /// let desc = MethodDescriptor::new(vec![string.array()], Type::Void);
+    /// assert_eq!(&desc.descriptor(), "([Ljava/lang/String;)V");
+    /// ```
+    pub fn descriptor(&self) -> String {
+        let mut descriptor = String::from("(");
+
+        for param in &self.params {
+            descriptor.push_str(&param.descriptor());
+        }
+
+        descriptor.push(')');
+        descriptor.push_str(&self.return_type.descriptor());
+        descriptor
+    }
+}
+
+impl fmt::Display for MethodDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.descriptor())
+    }
+}
+
+/// Gets the JVM bytecode descriptor of a method with the specified parameter and return types.
+///
+/// This is a shortcut for [`MethodDescriptor::from_parts`] followed by [`MethodDescriptor::descriptor`].
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::{method_descriptor, Type};
+///
+/// assert_eq!(&method_descriptor(&[Type::Long, Type::Int], &Type::Boolean), "(JI)Z");
+/// ```
+pub fn method_descriptor(params: &[Type], return_type: &Type) -> String {
+    MethodDescriptor::from_parts(params, return_type).descriptor()
+}
+
+/// Parses a method descriptor, replaces its return type with `f` applied to it and returns the new descriptor.
+///
+/// This is a shortcut for [`MethodDescriptor::parse`] followed by [`MethodDescriptor::map_return`].
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::{map_method_return, Type};
+///
+/// assert_eq!(&map_method_return("(Ljava/lang/String;)I", |_| Type::Void)?, "(Ljava/lang/String;)V");
+/// assert!(map_method_return("I", |_| Type::Void).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn map_method_return(desc: &str, f: impl FnOnce(&Type) -> Type) -> anyhow::Result<String> {
+    Ok(MethodDescriptor::parse(desc)?.map_return(f).descriptor())
+}
+
+/// Parses a method descriptor, replaces each parameter type with `f` applied to it and returns the new descriptor.
+///
+/// This is a shortcut for [`MethodDescriptor::parse`] followed by [`MethodDescriptor::map_params`].
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::{map_method_params, Type};
+///
+/// assert_eq!(&map_method_params("(IZ)I", |param| param.array())?, "([I[Z)I");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn map_method_params(desc: &str, f: impl FnMut(&Type) -> Type) -> anyhow::Result<String> {
+    Ok(MethodDescriptor::parse(desc)?.map_params(f).descriptor())
+}
+
+/// Gets the descriptor of a getter method (`()<field type>`) for a field with the descriptor `field_desc`.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::getter_descriptor;
+///
+/// assert_eq!(&getter_descriptor("I")?, "()I");
+/// assert!(getter_descriptor("(I)V").is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn getter_descriptor(field_desc: &str) -> anyhow::Result<String> {
+    let field_type = Type::from_descriptor(field_desc)?;
+    Ok(method_descriptor(&[], &field_type))
+}
+
+/// Gets the descriptor of a setter method (`(<field type>)V`) for a field with the descriptor `field_desc`.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::setter_descriptor;
+///
+/// assert_eq!(&setter_descriptor("Ljava/lang/String;")?, "(Ljava/lang/String;)V");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn setter_descriptor(field_desc: &str) -> anyhow::Result<String> {
+    let field_type = Type::from_descriptor(field_desc)?;
+    Ok(method_descriptor(&[field_type], &Type::Void))
+}
+
+/// Lazily parses the parameter types of a method descriptor.
+///
+/// Each parameter is parsed when the iterator is advanced, and iteration stops at the closing `)`.
+/// The return type and anything after it are not parsed. If the descriptor is invalid,
+/// an error is yielded and the iteration ends.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::{parse_method_params, ClassName, Type};
+///
+/// let params: Vec<Type> = parse_method_params("(I[Ljava/lang/String;J)V").collect::<anyhow::Result<_>>()?;
+/// let strings = ClassName::from_internal_name("java/lang/String").to_type().array();
+/// assert_eq!(params, vec![Type::Int, strings, Type::Long]);
+///
+/// assert_eq!(parse_method_params("(JI)V").next().unwrap()?, Type::Long);
+/// assert!(parse_method_params("(I").nth(1).unwrap().is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_method_params(desc: &str) -> MethodParamIter<'_> {
+    MethodParamIter { desc, rest: None, done: false }
+}
+
+/// An iterator over the parameter types of a method descriptor, created by [`parse_method_params`].
+#[derive(Debug, Clone)]
+pub struct MethodParamIter<'a> {
+    desc: &'a str,
+    /// The unparsed part of the parameter list, or `None` if the opening `(` hasn't been checked yet.
+    rest: Option<&'a str>,
+    done: bool,
+}
+
+impl<'a> MethodParamIter<'a> {
+    fn next_param(&mut self) -> anyhow::Result<Option<Type>> {
+        let rest = match self.rest {
+            Some(rest) => rest,
+            None => match self.desc.strip_prefix('(') {
+                Some(rest) => rest,
+                None => bail!("Method descriptor {} doesn't start with (", self.desc),
+            },
+        };
+
+        if rest.starts_with(')') {
+            return Ok(None);
+        }
+
+        if rest.is_empty() {
+            bail!("Unterminated parameter list in method descriptor {}", self.desc);
+        }
+
+        let (param, rest) = parse_type(rest)?;
+        self.rest = Some(rest);
+        Ok(Some(param))
+    }
+}
+
+impl<'a> Iterator for MethodParamIter<'a> {
+    type Item = anyhow::Result<Type>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.next_param().transpose();
+
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+
+        result
+    }
+}
@@ -15,7 +15,15 @@
 */
 
 mod class_name;
+mod class_name_pool;
+mod kind;
+mod method;
+mod remap;
 mod types;
 
 pub use class_name::*;
+pub use class_name_pool::*;
+pub use kind::*;
+pub use method::*;
+pub use remap::*;
 pub use types::*;
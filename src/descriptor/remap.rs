@@ -0,0 +1,145 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use crate::descriptor::{ClassName, MethodDescriptor, Type};
+
+/// Remaps the class names in a field or method descriptor.
+///
+/// `remap` is called with the internal name of each referenced class, and returns
+/// the new internal name or `None` to keep the class name unchanged.
+/// The descriptor is not validated.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::descriptor::remap_descriptor;
+///
+/// let remapped = remap_descriptor("(La;[La;I)Lb;", |class| match class {
+///     "a" => Some("com/example/Foo".to_owned()),
+///     _ => None,
+/// });
+/// assert_eq!(remapped, "(Lcom/example/Foo;[Lcom/example/Foo;I)Lb;");
+/// ```
+pub fn remap_descriptor(desc: &str, mut remap: impl FnMut(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(desc.len());
+    let mut rest = desc;
+
+    while let Some(start) = rest.find('L') {
+        result.push_str(&rest[..=start]);
+        let class_start = &rest[start + 1..];
+
+        match class_start.find(';') {
+            Some(end) => {
+                let class = &class_start[..end];
+
+                match remap(class) {
+                    Some(remapped) => result.push_str(&remapped),
+                    None => result.push_str(class),
+                }
+
+                result.push(';');
+                rest = &class_start[end + 1..];
+            }
+            None => {
+                rest = class_start;
+                break;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// A descriptor remapper that renames classes using a map and caches remapped descriptors.
+///
+/// Each distinct descriptor is parsed and remapped once, and later calls with the same descriptor
+/// return the cached result. This is useful when many members share a small set of descriptors.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use jvm_obfuscation_mappings::descriptor::CachingRemapper;
+///
+/// let remapper = CachingRemapper::new(HashMap::from([("a".to_owned(), "com/example/Foo".to_owned())]));
+///
+/// for _ in 0..1000 {
+///     assert_eq!(remapper.remap("(La;[La;I)Lb;")?, "(Lcom/example/Foo;[Lcom/example/Foo;I)Lb;");
+/// }
+///
+/// assert_eq!(remapper.parse_count(), 1);
+/// assert!(remapper.remap("(La").is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CachingRemapper {
+    renames: HashMap<String, String>,
+    cache: RefCell<HashMap<String, String>>,
+    parse_count: Cell<usize>,
+}
+
+impl CachingRemapper {
+    /// Creates a new caching remapper from a map of old internal class names to new ones.
+    pub fn new(renames: HashMap<String, String>) -> CachingRemapper {
+        CachingRemapper { renames, cache: RefCell::new(HashMap::new()), parse_count: Cell::new(0) }
+    }
+
+    /// Returns the new name of a class, or `None` if the class isn't renamed.
+    pub fn map_class(&self, internal_name: &str) -> Option<&str> {
+        self.renames.get(internal_name).map(String::as_str)
+    }
+
+    /// Remaps the class names in a field or method descriptor.
+    ///
+    /// Fails if the descriptor is invalid.
+    pub fn remap(&self, desc: &str) -> anyhow::Result<String> {
+        if let Some(remapped) = self.cache.borrow().get(desc) {
+            return Ok(remapped.clone());
+        }
+
+        self.parse_count.set(self.parse_count.get() + 1);
+
+        let remapped = if desc.starts_with('(') {
+            let parsed = MethodDescriptor::parse(desc)?;
+            let params: Vec<Type> = parsed.params().iter().map(|param| self.remap_type(param)).collect();
+            MethodDescriptor::new(params, self.remap_type(parsed.return_type())).descriptor()
+        } else {
+            self.remap_type(&Type::from_descriptor(desc)?).descriptor()
+        };
+
+        self.cache.borrow_mut().insert(desc.to_owned(), remapped.clone());
+        Ok(remapped)
+    }
+
+    /// Returns the number of descriptors that have been parsed, which is the number of cache misses.
+    pub fn parse_count(&self) -> usize {
+        self.parse_count.get()
+    }
+
+    fn remap_type(&self, ty: &Type) -> Type {
+        match ty {
+            Type::Object(class) => match self.map_class(class.internal_name()) {
+                Some(renamed) => Type::Object(ClassName::from_internal_name(renamed)),
+                None => ty.clone(),
+            },
+            Type::Array(element_type) => self.remap_type(element_type).array(),
+            _ => ty.clone(),
+        }
+    }
+}
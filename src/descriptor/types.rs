@@ -15,8 +15,14 @@
 */
 
 use std::fmt;
+use anyhow::bail;
 use crate::descriptor::ClassName;
 
+/// The maximum number of array dimensions accepted by [`Type::from_descriptor`].
+///
+/// This is the limit imposed by the JVM.
+pub const MAX_ARRAY_DEPTH: usize = 255;
+
 /// A JVM type.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Type {
@@ -56,6 +62,78 @@ pub enum Type {
 }
 
 impl Type {
+    /// Parses a type from a JVM bytecode descriptor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{ClassName, Type};
+    ///
+    /// let string = ClassName::from_binary_name("java.lang.String").to_type();
+    /// assert_eq!(Type::from_descriptor("[Ljava/lang/String;")?, string.array());
+    /// assert_eq!(Type::from_descriptor("J")?, Type::Long);
+    /// assert!(Type::from_descriptor("Ljava/lang/String").is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_descriptor(desc: &str) -> anyhow::Result<Type> {
+        Type::from_descriptor_with_max_array_depth(desc, MAX_ARRAY_DEPTH)
+    }
+
+    /// Parses a type from a JVM bytecode descriptor, allowing at most `max_array_depth` array dimensions.
+    ///
+    /// Array dimensions are counted without recursion, so descriptors with absurdly many
+    /// dimensions are rejected cleanly. The limit is capped at [`MAX_ARRAY_DEPTH`], since deeper types
+    /// aren't valid on the JVM and would overflow the stack when they're cloned or dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::Type;
+    ///
+    /// assert_eq!(Type::from_descriptor_with_max_array_depth("[[I", 2)?.array_depth(), 2);
+    /// assert!(Type::from_descriptor_with_max_array_depth("[[[I", 2).is_err());
+    ///
+    /// let deep = format!("{}I", "[".repeat(300));
+    /// assert!(Type::from_descriptor(&deep).is_err());
+    ///
+    /// let huge = format!("{}I", "[".repeat(1_000_000));
+    /// assert!(Type::from_descriptor_with_max_array_depth(&huge, usize::MAX).is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_descriptor_with_max_array_depth(desc: &str, max_array_depth: usize) -> anyhow::Result<Type> {
+        let (parsed, rest) = parse_type_with_max_array_depth(desc, max_array_depth.min(MAX_ARRAY_DEPTH))?;
+
+        if !rest.is_empty() {
+            bail!("Trailing characters {} in type descriptor {}", rest, desc);
+        }
+
+        Ok(parsed)
+    }
+
+    /// Parses a single type from the start of a string, returning the type and the unparsed remainder.
+    ///
+    /// This is useful for parsing descriptors embedded in larger strings. Like [`from_descriptor`][Self::from_descriptor],
+    /// this doesn't accept `void`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::Type;
+    ///
+    /// let (parsed, rest) = Type::parse_prefix("ILjava/lang/String;")?;
+    /// assert_eq!(parsed, Type::Int);
+    /// assert_eq!(rest, "Ljava/lang/String;");
+    ///
+    /// let (parsed, rest) = Type::parse_prefix(rest)?;
+    /// assert_eq!(parsed.descriptor(), "Ljava/lang/String;");
+    /// assert_eq!(rest, "");
+    /// assert!(Type::parse_prefix("Ljava/lang/String").is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn parse_prefix(s: &str) -> anyhow::Result<(Type, &str)> {
+        parse_type(s)
+    }
+
     /// Gets the JVM bytecode descriptor for this type.
     ///
     /// # Examples
@@ -110,6 +188,74 @@ impl Type {
         }
     }
 
+    /// Parses a type from the name it would use in Java, as returned by [`java_name`][Self::java_name].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{ClassName, Type};
+    ///
+    /// let string = ClassName::from_binary_name("java.lang.String").to_type();
+    /// assert_eq!(Type::from_java_name("java.lang.String[]")?, string.array());
+    /// assert_eq!(Type::from_java_name("int")?, Type::Int);
+    /// assert!(Type::from_java_name("").is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_java_name(name: &str) -> anyhow::Result<Type> {
+        if let Some(element_name) = name.strip_suffix("[]") {
+            return Ok(Type::from_java_name(element_name)?.array());
+        }
+
+        Ok(match name {
+            "" => bail!("Empty Java type name"),
+            "byte" => Type::Byte,
+            "short" => Type::Short,
+            "int" => Type::Int,
+            "long" => Type::Long,
+            "float" => Type::Float,
+            "double" => Type::Double,
+            "boolean" => Type::Boolean,
+            "char" => Type::Char,
+            "void" => Type::Void,
+            _ => Type::Object(ClassName::from_binary_name(name)),
+        })
+    }
+
+    /// Gets a human-readable name for this type using the given display options.
+    ///
+    /// Arrays are displayed with `[]` suffixes as in [`java_name`][Self::java_name].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{ClassName, DisplayOptions, Type};
+    ///
+    /// let strings = ClassName::from_internal_name("java/lang/String").to_type().array();
+    /// assert_eq!(strings.display_name(DisplayOptions::default()), "String[]");
+    /// let qualified = DisplayOptions { qualified: true, ..DisplayOptions::default() };
+    /// assert_eq!(strings.display_name(qualified), "java.lang.String[]");
+    ///
+    /// let entry = ClassName::from_internal_name("java/util/Map$Entry").to_type();
+    /// assert_eq!(entry.display_name(DisplayOptions::default()), "Map$Entry");
+    /// let nested_dots = DisplayOptions { nested_dots: true, ..DisplayOptions::default() };
+    /// assert_eq!(entry.display_name(nested_dots), "Map.Entry");
+    /// ```
+    pub fn display_name(&self, opts: DisplayOptions) -> String {
+        match self {
+            Type::Object(name) => {
+                let name = if opts.qualified { name.binary_name() } else { name.simple_name().to_owned() };
+
+                if opts.nested_dots {
+                    name.replace('$', ".")
+                } else {
+                    name
+                }
+            }
+            Type::Array(element_type) => format!("{}[]", element_type.display_name(opts)),
+            _ => self.java_name(),
+        }
+    }
+
     /// Returns an array type containing this type as its element type.
     ///
     /// # Examples
@@ -125,6 +271,26 @@ impl Type {
         Type::Array(Box::new(self.clone()))
     }
 
+    /// Checks whether this type is the pseudo-type `void`.
+    ///
+    /// `void` can only be used as a method return type, so it is rejected by [`from_descriptor`][Self::from_descriptor].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::{MethodDescriptor, Type};
+    ///
+    /// assert!(Type::Void.is_void());
+    /// assert!(!Type::Int.is_void());
+    /// assert!(Type::from_descriptor("V").is_err());
+    /// assert!(MethodDescriptor::parse("()V")?.return_type().is_void());
+    /// assert!(MethodDescriptor::parse("(V)V").is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn is_void(&self) -> bool {
+        matches!(self, Type::Void)
+    }
+
     /// Returns the depth of array layers in this type.
     ///
     /// All non-array types return 0. Arrays return 1 + their element type's array depth.
@@ -139,15 +305,110 @@ impl Type {
     /// assert_eq!(string.array().array().array().array_depth(), 3);
     /// ```
     pub fn array_depth(&self) -> u32 {
-        match self {
-            Type::Array(element_type) => 1 + element_type.array_depth(),
-            _ => 0,
+        let mut depth = 0;
+        let mut current = self;
+
+        while let Type::Array(element_type) = current {
+            depth += 1;
+            current = element_type;
         }
+
+        depth
+    }
+
+    /// Returns whether this type occupies two local variable slots.
+    ///
+    /// Only `long` and `double` are two slots wide; all other types, including arrays of them, take a single slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::descriptor::Type;
+    ///
+    /// assert!(Type::Long.occupies_two_slots());
+    /// assert!(Type::Double.occupies_two_slots());
+    /// assert!(!Type::Int.occupies_two_slots());
+    /// assert!(!Type::Long.array().occupies_two_slots());
+    /// ```
+    pub fn occupies_two_slots(&self) -> bool {
+        matches!(self, Type::Long | Type::Double)
     }
 }
 
+/// Options for [`Type::display_name`].
+///
+/// By default, class names are displayed as simple names with `$` separating nested classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DisplayOptions {
+    /// Whether class names include their packages.
+    pub qualified: bool,
+    /// Whether nested classes are separated using `.` instead of `$`.
+    pub nested_dots: bool,
+}
+
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.descriptor())
     }
 }
+
+/// Parses a single method return type from the start of `s`, returning the type and the unparsed remainder.
+///
+/// Unlike [`parse_type`], this accepts `void`.
+pub(crate) fn parse_return_type(s: &str) -> anyhow::Result<(Type, &str)> {
+    match s.strip_prefix('V') {
+        Some(rest) => Ok((Type::Void, rest)),
+        None => parse_type(s),
+    }
+}
+
+/// Parses a single type from the start of `s`, returning the type and the unparsed remainder.
+///
+/// `void` isn't accepted, as it can't be the type of a field or a parameter.
+pub(crate) fn parse_type(s: &str) -> anyhow::Result<(Type, &str)> {
+    parse_type_with_max_array_depth(s, MAX_ARRAY_DEPTH)
+}
+
+fn parse_type_with_max_array_depth(s: &str, max_array_depth: usize) -> anyhow::Result<(Type, &str)> {
+    let array_depth = s.len() - s.trim_start_matches('[').len();
+
+    if array_depth > max_array_depth {
+        bail!("Array type descriptor has {} dimensions, more than the maximum of {}", array_depth, max_array_depth);
+    }
+
+    let (mut parsed, rest) = parse_non_array_type(&s[array_depth..])?;
+
+    for _ in 0..array_depth {
+        parsed = Type::Array(Box::new(parsed));
+    }
+
+    Ok((parsed, rest))
+}
+
+fn parse_non_array_type(s: &str) -> anyhow::Result<(Type, &str)> {
+    let rest = s.get(1..).unwrap_or("");
+
+    let parsed = match s.chars().next() {
+        Some('L') => {
+            let end = match rest.find(';') {
+                Some(end) if end > 0 => end,
+                _ => bail!("Invalid object type descriptor {}", s),
+            };
+
+            return Ok((Type::Object(ClassName::from_internal_name(&rest[..end])), &rest[end + 1..]));
+        }
+        Some('B') => Type::Byte,
+        Some('S') => Type::Short,
+        Some('I') => Type::Int,
+        Some('J') => Type::Long,
+        Some('F') => Type::Float,
+        Some('D') => Type::Double,
+        Some('Z') => Type::Boolean,
+        Some('C') => Type::Char,
+        Some('V') => bail!("void is only allowed as a method return type"),
+        Some(_) => bail!("Invalid type descriptor {}", s),
+        None => bail!("Empty type descriptor"),
+    };
+
+    Ok((parsed, rest))
+}
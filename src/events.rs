@@ -0,0 +1,352 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A single call to a [`MappingVisitor`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MappingEvent {
+    /// [`MappingVisitor::visit_header`]
+    Header,
+    /// [`MappingVisitor::visit_namespaces`]
+    Namespaces { src_namespace: String, dst_namespaces: Vec<String> },
+    /// [`MappingVisitor::visit_metadata`]
+    Metadata { key: String, value: String },
+    /// [`MappingVisitor::visit_content`]
+    Content,
+    /// [`MappingVisitor::visit_content_size`]
+    ContentSize { classes: usize },
+    /// [`MappingVisitor::visit_source_position`]
+    SourcePosition { line: usize },
+    /// [`MappingVisitor::visit_class`]
+    Class { src_name: String },
+    /// [`MappingVisitor::visit_field`]
+    Field { src_name: String, src_desc: Option<String> },
+    /// [`MappingVisitor::visit_method`]
+    Method { src_name: String, src_desc: Option<String> },
+    /// [`MappingVisitor::visit_method_arg`]
+    MethodArg { arg_position: i32, lv_index: i32, src_name: Option<String> },
+    /// [`MappingVisitor::visit_method_var`]
+    MethodVar { lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<String> },
+    /// [`MappingVisitor::visit_dst_name`]
+    DstName { target_kind: MappedElementKind, namespace: usize, name: String },
+    /// [`MappingVisitor::visit_dst_desc`]
+    DstDesc { target_kind: MappedElementKind, namespace: usize, desc: String },
+    /// [`MappingVisitor::visit_element_content`]
+    ElementContent { target_kind: MappedElementKind },
+    /// [`MappingVisitor::visit_comment`]
+    Comment { target_kind: MappedElementKind, comment: String },
+    /// [`MappingVisitor::visit_end`]
+    End,
+}
+
+impl MappingEvent {
+    /// Calls the visit method corresponding to this event on a visitor.
+    ///
+    /// Returns the result of the visit method, or `true` for methods that don't return anything.
+    pub fn visit<V: MappingVisitor + ?Sized>(&self, visitor: &mut V) -> VisitResult<bool> {
+        match self {
+            MappingEvent::Header => visitor.visit_header(),
+            MappingEvent::Namespaces { src_namespace, dst_namespaces } => {
+                let dst_namespaces: Vec<&str> = dst_namespaces.iter().map(String::as_str).collect();
+                visitor.visit_namespaces(src_namespace, &dst_namespaces)?;
+                Ok(true)
+            }
+            MappingEvent::Metadata { key, value } => {
+                visitor.visit_metadata(key, value)?;
+                Ok(true)
+            }
+            MappingEvent::Content => visitor.visit_content(),
+            MappingEvent::ContentSize { classes } => {
+                visitor.visit_content_size(*classes)?;
+                Ok(true)
+            }
+            MappingEvent::SourcePosition { line } => {
+                visitor.visit_source_position(*line)?;
+                Ok(true)
+            }
+            MappingEvent::Class { src_name } => visitor.visit_class(src_name),
+            MappingEvent::Field { src_name, src_desc } => visitor.visit_field(src_name, src_desc.as_deref()),
+            MappingEvent::Method { src_name, src_desc } => visitor.visit_method(src_name, src_desc.as_deref()),
+            MappingEvent::MethodArg { arg_position, lv_index, src_name } => {
+                visitor.visit_method_arg(*arg_position, *lv_index, src_name.as_deref())
+            }
+            MappingEvent::MethodVar { lvt_row_index, lv_index, start_op_idx, src_name } => {
+                visitor.visit_method_var(*lvt_row_index, *lv_index, *start_op_idx, src_name.as_deref())
+            }
+            MappingEvent::DstName { target_kind, namespace, name } => {
+                visitor.visit_dst_name(*target_kind, *namespace, name)?;
+                Ok(true)
+            }
+            MappingEvent::DstDesc { target_kind, namespace, desc } => {
+                visitor.visit_dst_desc(*target_kind, *namespace, desc)?;
+                Ok(true)
+            }
+            MappingEvent::ElementContent { target_kind } => visitor.visit_element_content(*target_kind),
+            MappingEvent::Comment { target_kind, comment } => {
+                visitor.visit_comment(*target_kind, comment)?;
+                Ok(true)
+            }
+            MappingEvent::End => visitor.visit_end(),
+        }
+    }
+
+    /// Returns the kind of the element this event starts, or `None` if it doesn't start an element.
+    fn started_element(&self) -> Option<MappedElementKind> {
+        match self {
+            MappingEvent::Class { .. } => Some(MappedElementKind::Class),
+            MappingEvent::Field { .. } => Some(MappedElementKind::Field),
+            MappingEvent::Method { .. } => Some(MappedElementKind::Method),
+            MappingEvent::MethodArg { .. } => Some(MappedElementKind::MethodArg),
+            MappingEvent::MethodVar { .. } => Some(MappedElementKind::MethodVar),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of the element this event belongs to without starting it.
+    fn target_kind(&self) -> Option<MappedElementKind> {
+        match self {
+            MappingEvent::DstName { target_kind, .. }
+            | MappingEvent::DstDesc { target_kind, .. }
+            | MappingEvent::ElementContent { target_kind }
+            | MappingEvent::Comment { target_kind, .. } => Some(*target_kind),
+            _ => None,
+        }
+    }
+}
+
+/// A visitor that records all visits as [`MappingEvent`]s.
+///
+/// Every visit is accepted, so the recorded events contain the complete input.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::events::{EventSink, EventVisitor, MappingEvent};
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tm\t(I)V\tb\tsetValue\n\
+///             \t\tp\t1\t\tvalue\n";
+/// let mut sink = EventSink::new();
+/// Tiny2Reader::read_str(tiny, &mut sink)?;
+/// let events = sink.into_events();
+/// assert_eq!(events[0], MappingEvent::Header);
+/// assert_eq!(events.last(), Some(&MappingEvent::End));
+///
+/// let mut replayer = EventVisitor::new(MappingTree::new());
+/// for event in &events {
+///     replayer.visit_event(event)?;
+/// }
+///
+/// let mut expected = MappingTree::new();
+/// Tiny2Reader::read_str(tiny, &mut expected)?;
+/// assert_eq!(replayer.into_inner(), expected);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EventSink {
+    events: Vec<MappingEvent>,
+}
+
+impl EventSink {
+    /// Creates a new, empty event sink.
+    pub fn new() -> EventSink {
+        EventSink::default()
+    }
+
+    /// Returns the recorded events.
+    pub fn events(&self) -> &[MappingEvent] {
+        &self.events
+    }
+
+    /// Returns the recorded events, consuming the sink.
+    pub fn into_events(self) -> Vec<MappingEvent> {
+        self.events
+    }
+}
+
+impl MappingVisitor for EventSink {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        HashSet::new()
+    }
+
+    fn reset(&mut self) {
+        self.events.clear();
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.events.push(MappingEvent::Header);
+        Ok(true)
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.events.push(MappingEvent::Namespaces {
+            src_namespace: src_namespace.to_owned(),
+            dst_namespaces: dst_namespaces.iter().map(|&ns| ns.to_owned()).collect(),
+        });
+        Ok(())
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.events.push(MappingEvent::Metadata { key: key.to_owned(), value: value.to_owned() });
+        Ok(())
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.events.push(MappingEvent::Content);
+        Ok(true)
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        self.events.push(MappingEvent::ContentSize { classes });
+        Ok(())
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        self.events.push(MappingEvent::SourcePosition { line });
+        Ok(())
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.events.push(MappingEvent::Class { src_name: src_name.to_owned() });
+        Ok(true)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.events.push(MappingEvent::Field { src_name: src_name.to_owned(), src_desc: src_desc.map(str::to_owned) });
+        Ok(true)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.events.push(MappingEvent::Method { src_name: src_name.to_owned(), src_desc: src_desc.map(str::to_owned) });
+        Ok(true)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.events.push(MappingEvent::MethodArg { arg_position, lv_index, src_name: src_name.map(str::to_owned) });
+        Ok(true)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.events.push(MappingEvent::MethodVar {
+            lvt_row_index,
+            lv_index,
+            start_op_idx,
+            src_name: src_name.map(str::to_owned),
+        });
+        Ok(true)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.events.push(MappingEvent::End);
+        Ok(true)
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        self.events.push(MappingEvent::DstName { target_kind, namespace, name: name.to_owned() });
+        Ok(())
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        self.events.push(MappingEvent::DstDesc { target_kind, namespace, desc: desc.to_owned() });
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        self.events.push(MappingEvent::ElementContent { target_kind });
+        Ok(true)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.events.push(MappingEvent::Comment { target_kind, comment: comment.to_owned() });
+        Ok(())
+    }
+}
+
+/// Replays a stream of [`MappingEvent`]s into a [`MappingVisitor`].
+///
+/// The results of the visitor are respected: if a visit method returns `false`,
+/// the events it would have skipped are dropped. Since a stream can't be rewound,
+/// visitors that need multiple passes should be fed through a [`MappingTree`](crate::tree::MappingTree).
+///
+/// See [`EventSink`] for an example.
+pub struct EventVisitor<V> {
+    next: V,
+    skip_header: bool,
+    skip_content: bool,
+    /// The nesting level of the element whose remaining events and children are skipped.
+    skip_level: Option<u32>,
+}
+
+impl<V: MappingVisitor> EventVisitor<V> {
+    /// Creates a new event visitor.
+    pub fn new(next: V) -> Self {
+        EventVisitor { next, skip_header: false, skip_content: false, skip_level: None }
+    }
+
+    /// Returns the wrapped visitor.
+    pub fn into_inner(self) -> V {
+        self.next
+    }
+
+    /// Passes an event on to the wrapped visitor unless it is skipped.
+    pub fn visit_event(&mut self, event: &MappingEvent) -> VisitResult<()> {
+        match event {
+            MappingEvent::Namespaces { .. } | MappingEvent::Metadata { .. } if self.skip_header => return Ok(()),
+            MappingEvent::Content => self.skip_header = false,
+            MappingEvent::End => {
+                self.skip_header = false;
+                self.skip_content = false;
+                self.skip_level = None;
+            }
+            _ if self.skip_content => return Ok(()),
+            _ => {}
+        }
+
+        let level = if let Some(kind) = event.started_element() {
+            match self.skip_level {
+                Some(skip_level) if kind.level() > skip_level => return Ok(()),
+                _ => self.skip_level = None,
+            }
+
+            Some(kind.level())
+        } else if let Some(kind) = event.target_kind() {
+            match self.skip_level {
+                Some(skip_level) if kind.level() >= skip_level => return Ok(()),
+                _ => {}
+            }
+
+            Some(kind.level())
+        } else {
+            None
+        };
+
+        if !event.visit(&mut self.next)? {
+            match event {
+                MappingEvent::Header => self.skip_header = true,
+                MappingEvent::Content => self.skip_content = true,
+                _ => self.skip_level = level,
+            }
+        }
+
+        Ok(())
+    }
+}
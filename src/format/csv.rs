@@ -0,0 +1,142 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A writer for CSV tables of class names as a [`MappingVisitor`] that outputs to [`std::fmt::Write`].
+///
+/// The first row contains the namespace names, and each following row contains the source name
+/// and the destination names of a class. Missing destination names are written as empty cells,
+/// and cells containing commas, quotes or line breaks are quoted. Members are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::csv::ClassCsvWriter;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tintermediary\tnamed\n\
+///             c\ta\tclass_1\tcom/example/Foo\n\
+///             \tf\tI\tb\tfield_1\tvalue\n\
+///             c\tb\tclass_2\tcom/example/Odd,Name\n\
+///             c\tc\tclass_3\n";
+/// let mut csv = String::new();
+/// Tiny2Reader::read_str(tiny, &mut ClassCsvWriter::new(&mut csv))?;
+///
+/// assert_eq!(csv, "official,intermediary,named\n\
+///                  a,class_1,com/example/Foo\n\
+///                  b,class_2,\"com/example/Odd,Name\"\n\
+///                  c,class_3,\n");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ClassCsvWriter<W: std::fmt::Write> {
+    write: W,
+    src_name: String,
+    dst_names: Vec<Option<String>>,
+}
+
+impl<W: std::fmt::Write> ClassCsvWriter<W> {
+    /// Creates a new class CSV writer.
+    pub fn new(write: W) -> ClassCsvWriter<W> {
+        ClassCsvWriter { write, src_name: String::new(), dst_names: Vec::new() }
+    }
+
+    fn write_row<'a>(&mut self, cells: impl IntoIterator<Item = &'a str>) -> VisitResult<()> {
+        for (index, cell) in cells.into_iter().enumerate() {
+            if index > 0 {
+                self.write.write_char(',')?;
+            }
+
+            if cell.contains([',', '"', '\n', '\r']) {
+                write!(self.write, "\"{}\"", cell.replace('"', "\"\""))?;
+            } else {
+                self.write.write_str(cell)?;
+            }
+        }
+
+        self.write.write_char('\n')?;
+        Ok(())
+    }
+}
+
+impl<W: std::fmt::Write> MappingVisitor for ClassCsvWriter<W> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        HashSet::from([MappingFlag::NeedsUniqueness])
+    }
+
+    fn supports_comments(&self) -> bool {
+        false
+    }
+
+    fn supports_metadata(&self) -> bool {
+        false
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.dst_names = vec![None; dst_namespaces.len()];
+        self.write_row([src_namespace].into_iter().chain(dst_namespaces.iter().copied()))
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.src_name = src_name.to_owned();
+        self.dst_names.fill(None);
+        Ok(true)
+    }
+
+    fn visit_field(&mut self, _src_name: &str, _src_desc: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_method(&mut self, _src_name: &str, _src_desc: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_method_arg(&mut self, _arg_position: i32, _lv_index: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_method_var(&mut self, _lvt_row_index: i32, _lv_index: i32, _start_op_idx: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Class {
+            if let Some(dst_name) = self.dst_names.get_mut(namespace) {
+                *dst_name = Some(name.to_owned());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        if target_kind == MappedElementKind::Class {
+            let src_name = std::mem::take(&mut self.src_name);
+            let dst_names = std::mem::take(&mut self.dst_names);
+            self.write_row([src_name.as_str()].into_iter().chain(dst_names.iter().map(|name| name.as_deref().unwrap_or(""))))?;
+            self.dst_names = dst_names;
+        }
+
+        // The class row is complete, so its members don't need to be visited.
+        Ok(false)
+    }
+
+    fn visit_comment(&mut self, _target_kind: MappedElementKind, _comment: &str) -> VisitResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,473 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use std::io::BufRead;
+use anyhow::{anyhow, bail, Context};
+use crate::MappedElementKind;
+use crate::format::{check_not_empty, line_error};
+use crate::tree::MappingTree;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The source namespace used by [`EnigmaSingleFileReader::read`].
+pub const ENIGMA_SRC_NAMESPACE: &str = "source";
+/// The destination namespace used by [`EnigmaSingleFileReader::read`].
+pub const ENIGMA_DST_NAMESPACE: &str = "target";
+
+/// A reader for single-file Enigma mappings, where all top-level `CLASS` entries are in one file.
+///
+/// Nested classes can either be written inside their outer classes using their simple names,
+/// or as top-level classes using their full names. Access modifiers (`ACC:` tokens) are ignored,
+/// and consecutive `COMMENT` lines are joined with newlines.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::enigma::EnigmaSingleFileReader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let enigma = "CLASS a com/example/Foo\n\
+///               \tCOMMENT The foo.\n\
+///               \tMETHOD b setValue (I)V\n\
+///               \t\tARG 1 value\n\
+///               \tCLASS c Bar\n\
+///               \t\tFIELD d count I\n";
+/// let mut tree = MappingTree::new();
+/// EnigmaSingleFileReader::read_str(enigma, &mut tree)?;
+///
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("com/example/Foo"));
+/// assert_eq!(class.comment(), Some("The foo."));
+/// assert_eq!(class.methods()[0].args()[0].dst_name(0), Some("value"));
+/// let nested = tree.class("a$c").unwrap();
+/// assert_eq!(nested.dst_name(0), Some("com/example/Foo$Bar"));
+/// assert_eq!(nested.fields()[0].dst_name(0), Some("count"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct EnigmaSingleFileReader;
+
+impl EnigmaSingleFileReader {
+    /// Reads single-file Enigma mappings from a string using the namespaces
+    /// [`ENIGMA_SRC_NAMESPACE`] and [`ENIGMA_DST_NAMESPACE`].
+    pub fn read_str<V: MappingVisitor + ?Sized>(input: &str, visitor: &mut V) -> anyhow::Result<()> {
+        EnigmaSingleFileReader::read(input.as_bytes(), visitor)
+    }
+
+    /// Reads single-file Enigma mappings using the namespaces [`ENIGMA_SRC_NAMESPACE`] and [`ENIGMA_DST_NAMESPACE`].
+    pub fn read<R: BufRead, V: MappingVisitor + ?Sized>(reader: R, visitor: &mut V) -> anyhow::Result<()> {
+        EnigmaSingleFileReader::read_with_namespaces(reader, ENIGMA_SRC_NAMESPACE, ENIGMA_DST_NAMESPACE, visitor)
+    }
+
+    /// Reads single-file Enigma mappings using custom namespace names.
+    ///
+    /// The members of an outer class can continue after its nested classes, so visitors that need
+    /// uniqueness or multiple passes are fed through an intermediate [`MappingTree`].
+    pub fn read_with_namespaces<R: BufRead, V: MappingVisitor + ?Sized>(
+        mut reader: R,
+        src_namespace: &str,
+        dst_namespace: &str,
+        visitor: &mut V,
+    ) -> anyhow::Result<()> {
+        check_not_empty(&mut reader, "CLASS entries")?;
+        let flags = visitor.flags();
+
+        if flags.contains(&MappingFlag::NeedsUniqueness) || flags.contains(&MappingFlag::NeedsMultiplePasses) {
+            let mut tree = MappingTree::new();
+            EnigmaSingleFileReader::read_with_namespaces(reader, src_namespace, dst_namespace, &mut tree)?;
+            return tree.accept(visitor);
+        }
+
+        if visitor.visit_header()? {
+            visitor.visit_namespaces(src_namespace, &[dst_namespace])?;
+        }
+
+        if visitor.visit_content()? {
+            let mut state = ReadState {
+                classes: Vec::new(),
+                visited_class: None,
+                element_kinds: Vec::new(),
+                skip_level: None,
+                comment: None,
+            };
+
+            for (line_number, line) in reader.lines().enumerate() {
+                let line = line.map_err(|error| line_error(error, line_number + 1))?;
+                visitor.visit_source_position(line_number + 1)?;
+                state.read_line(&line, visitor)
+                    .with_context(|| format!("Invalid Enigma mappings on line {}", line_number + 1))?;
+            }
+
+            state.flush_comment(visitor)?;
+        }
+
+        visitor.visit_end()?;
+        Ok(())
+    }
+}
+
+/// A class entry that encloses the lines after it.
+struct OpenClass {
+    src_name: String,
+    /// The destination name, or the source name if the class is unmapped.
+    dst_name: String,
+}
+
+/// The state of an [`EnigmaSingleFileReader`] while reading lines.
+struct ReadState {
+    /// The enclosing classes of the current line, indexed by indentation.
+    classes: Vec<OpenClass>,
+    /// The source name of the class that was last visited and whether it was accepted.
+    visited_class: Option<(String, bool)>,
+    /// The kinds of the current elements at each indentation level.
+    element_kinds: Vec<MappedElementKind>,
+    /// The indentation level of the element whose children are being skipped.
+    skip_level: Option<usize>,
+    /// The pending comment lines and the kind of their element.
+    comment: Option<(MappedElementKind, String)>,
+}
+
+impl ReadState {
+    fn read_line<V: MappingVisitor + ?Sized>(&mut self, line: &str, visitor: &mut V) -> anyhow::Result<()> {
+        let indent = line.bytes().take_while(|&b| b == b'\t').count();
+        let content = &line[indent..];
+
+        if content.is_empty() {
+            return Ok(());
+        }
+
+        let (keyword, rest) = content.split_once(' ').unwrap_or((content, ""));
+
+        if keyword != "COMMENT" {
+            self.flush_comment(visitor)?;
+        }
+
+        if keyword == "CLASS" {
+            self.skip_level = None;
+            return self.read_class(indent, rest, visitor);
+        }
+
+        match self.skip_level {
+            Some(level) if indent > level => return Ok(()),
+            _ => self.skip_level = None,
+        }
+
+        self.element_kinds.truncate(indent);
+        let tokens: Vec<&str> = rest.split(' ').filter(|token| !token.starts_with("ACC:")).collect();
+
+        match (keyword, tokens.as_slice()) {
+            ("FIELD", [src_name, dst_name @ .., src_desc]) if dst_name.len() <= 1 => {
+                self.read_member(indent, MappedElementKind::Field, src_name, src_desc, dst_name.first().copied(), visitor)?;
+            }
+            ("METHOD", [src_name, dst_name @ .., src_desc]) if dst_name.len() <= 1 => {
+                self.read_member(indent, MappedElementKind::Method, src_name, src_desc, dst_name.first().copied(), visitor)?;
+            }
+            ("ARG", [lv_index, dst_name]) => {
+                if indent.checked_sub(1).and_then(|level| self.element_kinds.get(level)) != Some(&MappedElementKind::Method) {
+                    bail!("Argument outside of a method");
+                }
+
+                let lv_index = lv_index.parse().with_context(|| format!("Invalid local variable index {}", lv_index))?;
+                self.element_kinds.push(MappedElementKind::MethodArg);
+
+                if visitor.visit_method_arg(-1, lv_index, None)? {
+                    visitor.visit_dst_name(MappedElementKind::MethodArg, 0, dst_name)?;
+
+                    if !visitor.visit_element_content(MappedElementKind::MethodArg)? {
+                        self.skip_level = Some(indent);
+                    }
+                } else {
+                    self.skip_level = Some(indent);
+                }
+            }
+            ("COMMENT", _) => {
+                let target_kind = *indent.checked_sub(1)
+                    .and_then(|level| self.element_kinds.get(level))
+                    .ok_or_else(|| anyhow!("Comment without an element"))?;
+
+                if target_kind == MappedElementKind::Class && !matches!(self.visited_class, Some((_, true))) {
+                    return Ok(());
+                }
+
+                match &mut self.comment {
+                    Some((_, comment)) => {
+                        comment.push('\n');
+                        comment.push_str(rest);
+                    }
+                    None => self.comment = Some((target_kind, rest.to_owned())),
+                }
+            }
+            _ => bail!("Unknown line: {}", line),
+        }
+
+        Ok(())
+    }
+
+    fn read_class<V: MappingVisitor + ?Sized>(&mut self, indent: usize, rest: &str, visitor: &mut V) -> anyhow::Result<()> {
+        let tokens: Vec<&str> = rest.split(' ').filter(|token| !token.starts_with("ACC:")).collect();
+        let (src_name, dst_name) = match tokens.as_slice() {
+            [src_name] => (*src_name, None),
+            [src_name, dst_name] => (*src_name, Some(*dst_name)),
+            _ => bail!("Invalid class: CLASS {}", rest),
+        };
+
+        if indent > self.classes.len() {
+            bail!("Nested class {} without an outer class", src_name);
+        }
+
+        self.classes.truncate(indent);
+        let (src_name, dst_name) = match self.classes.last() {
+            Some(outer) => (
+                format!("{}${}", outer.src_name, src_name),
+                dst_name.map(|dst_name| format!("{}${}", outer.dst_name, dst_name)),
+            ),
+            None => (src_name.to_owned(), dst_name.map(str::to_owned)),
+        };
+
+        self.element_kinds.truncate(indent);
+        self.element_kinds.push(MappedElementKind::Class);
+        self.classes.push(OpenClass { src_name: src_name.clone(), dst_name: dst_name.clone().unwrap_or_else(|| src_name.clone()) });
+
+        let mut visit = visitor.visit_class(&src_name)?;
+
+        if visit {
+            if let Some(dst_name) = &dst_name {
+                visitor.visit_dst_name(MappedElementKind::Class, 0, dst_name)?;
+            }
+
+            visit = visitor.visit_element_content(MappedElementKind::Class)?;
+        }
+
+        self.visited_class = Some((src_name, visit));
+        Ok(())
+    }
+
+    fn read_member<V: MappingVisitor + ?Sized>(
+        &mut self,
+        indent: usize,
+        kind: MappedElementKind,
+        src_name: &str,
+        src_desc: &str,
+        dst_name: Option<&str>,
+        visitor: &mut V,
+    ) -> anyhow::Result<()> {
+        let owner = indent.checked_sub(1)
+            .filter(|&level| self.element_kinds.get(level) == Some(&MappedElementKind::Class))
+            .and_then(|level| self.classes.get(level))
+            .ok_or_else(|| anyhow!("Member {} outside of a class", src_name))?;
+
+        // Members of an outer class can follow its nested classes, so the outer class is visited again.
+        if !matches!(&self.visited_class, Some((visited, _)) if visited == &owner.src_name) {
+            let visit = visitor.visit_class(&owner.src_name)? && visitor.visit_element_content(MappedElementKind::Class)?;
+            self.visited_class = Some((owner.src_name.clone(), visit));
+        }
+
+        self.element_kinds.push(kind);
+
+        if !matches!(self.visited_class, Some((_, true))) {
+            self.skip_level = Some(indent);
+            return Ok(());
+        }
+
+        let visit = match kind {
+            MappedElementKind::Field => visitor.visit_field(src_name, Some(src_desc))?,
+            _ => visitor.visit_method(src_name, Some(src_desc))?,
+        };
+
+        if visit {
+            if let Some(dst_name) = dst_name {
+                visitor.visit_dst_name(kind, 0, dst_name)?;
+            }
+
+            if !visitor.visit_element_content(kind)? {
+                self.skip_level = Some(indent);
+            }
+        } else {
+            self.skip_level = Some(indent);
+        }
+
+        Ok(())
+    }
+
+    fn flush_comment<V: MappingVisitor + ?Sized>(&mut self, visitor: &mut V) -> anyhow::Result<()> {
+        if let Some((target_kind, comment)) = self.comment.take() {
+            visitor.visit_comment(target_kind, &comment)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A single-file Enigma writer as a [`MappingVisitor`] that outputs to [`std::fmt::Write`].
+///
+/// Only the first destination namespace is written. All classes, including nested classes, are written
+/// as top-level `CLASS` entries using their full names. Arguments without destination names
+/// and local variables are dropped, and multi-line comments are split into multiple `COMMENT` lines.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::enigma::EnigmaSingleFileWriter;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tm\t(I)V\tb\tsetValue\n\
+///             \t\tp\t1\t\tvalue\n\
+///             \t\tc\tSets the value.\n";
+/// let mut tree = MappingTree::new();
+/// Tiny2Reader::read_str(tiny, &mut tree)?;
+///
+/// let mut enigma = String::new();
+/// tree.accept(&mut EnigmaSingleFileWriter::new(&mut enigma))?;
+/// assert_eq!(enigma, "CLASS a com/example/Foo\n\
+///                     \tMETHOD b setValue (I)V\n\
+///                     \t\tCOMMENT Sets the value.\n\
+///                     \t\tARG 1 value\n");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct EnigmaSingleFileWriter<W: std::fmt::Write> {
+    write: W,
+    src_name: String,
+    src_desc: Option<String>,
+    lv_index: i32,
+    dst_name: Option<String>,
+    /// Whether the current argument has been written, since arguments without names are dropped.
+    arg_written: bool,
+}
+
+impl<W: std::fmt::Write> EnigmaSingleFileWriter<W> {
+    /// Creates a new single-file Enigma writer.
+    pub fn new(write: W) -> EnigmaSingleFileWriter<W> {
+        EnigmaSingleFileWriter {
+            write,
+            src_name: String::new(),
+            src_desc: None,
+            lv_index: 0,
+            dst_name: None,
+            arg_written: false,
+        }
+    }
+
+    fn write_indent(&mut self, level: u32) -> VisitResult<()> {
+        for _ in 0..level {
+            self.write.write_char('\t')?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: std::fmt::Write> MappingVisitor for EnigmaSingleFileWriter<W> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        HashSet::from([MappingFlag::NeedsUniqueness, MappingFlag::NeedsSrcFieldDesc, MappingFlag::NeedsSrcMethodDesc])
+    }
+
+    fn supports_metadata(&self) -> bool {
+        false
+    }
+
+    fn visit_namespaces(&mut self, _src_namespace: &str, _dst_namespaces: &[&str]) -> VisitResult<()> {
+        Ok(())
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.src_name = src_name.to_owned();
+        self.dst_name = None;
+        Ok(true)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.src_name = src_name.to_owned();
+        self.src_desc = src_desc.map(str::to_owned);
+        self.dst_name = None;
+        Ok(true)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.src_name = src_name.to_owned();
+        self.src_desc = src_desc.map(str::to_owned);
+        self.dst_name = None;
+        Ok(true)
+    }
+
+    fn visit_method_arg(&mut self, _arg_position: i32, lv_index: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        self.lv_index = lv_index;
+        self.dst_name = None;
+        Ok(true)
+    }
+
+    fn visit_method_var(&mut self, _lvt_row_index: i32, _lv_index: i32, _start_op_idx: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_dst_name(&mut self, _target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if namespace == 0 {
+            self.dst_name = Some(name.to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        let dst_name = self.dst_name.take();
+
+        let keyword = match target_kind {
+            MappedElementKind::Class => "CLASS",
+            MappedElementKind::Field => "FIELD",
+            MappedElementKind::Method => "METHOD",
+            MappedElementKind::MethodArg => {
+                self.arg_written = dst_name.is_some();
+
+                if let Some(dst_name) = dst_name {
+                    self.write_indent(target_kind.level())?;
+                    writeln!(self.write, "ARG {} {}", self.lv_index, dst_name)?;
+                }
+
+                return Ok(self.arg_written);
+            }
+            MappedElementKind::MethodVar => return Ok(false),
+        };
+
+        self.write_indent(target_kind.level())?;
+        write!(self.write, "{} {}", keyword, self.src_name)?;
+
+        if let Some(dst_name) = dst_name {
+            write!(self.write, " {}", dst_name)?;
+        }
+
+        if target_kind != MappedElementKind::Class {
+            let src_desc = self.src_desc.as_deref().ok_or_else(|| anyhow!("EnigmaSingleFileWriter needs src desc!"))?;
+            write!(self.write, " {}", src_desc)?;
+        }
+
+        writeln!(self.write)?;
+        Ok(true)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::MethodArg && !self.arg_written {
+            return Ok(());
+        }
+
+        for line in comment.split('\n') {
+            self.write_indent(target_kind.level() + 1)?;
+            writeln!(self.write, "COMMENT {}", line)?;
+        }
+
+        Ok(())
+    }
+}
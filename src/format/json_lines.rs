@@ -0,0 +1,145 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use serde_json::json;
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A writer as a [`MappingVisitor`] that outputs one JSON object per line to [`std::fmt::Write`].
+///
+/// The first line describes the namespaces, and each class, field and method gets a line with
+/// its kind, source name and destination names (`null` where missing). Members also include
+/// the source name of their owner and their source descriptor. Arguments, variables,
+/// comments and metadata are dropped.
+///
+/// This writer is only available with the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::json_lines::JsonLinesWriter;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tf\tI\tb\tcount\n";
+/// let mut output = String::new();
+/// Tiny2Reader::read_str(tiny, &mut JsonLinesWriter::new(&mut output))?;
+///
+/// let lines: Vec<serde_json::Value> = output.lines().map(serde_json::from_str).collect::<Result<_, _>>()?;
+/// assert_eq!(lines[0], serde_json::json!({ "kind": "namespaces", "src": "official", "dst": ["named"] }));
+/// assert_eq!(lines[1], serde_json::json!({ "kind": "class", "src": "a", "dst": ["com/example/Foo"] }));
+/// assert_eq!(lines[2], serde_json::json!({ "kind": "field", "owner": "a", "src": "b", "desc": "I", "dst": ["count"] }));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct JsonLinesWriter<W: std::fmt::Write> {
+    write: W,
+    class_src_name: String,
+    member_src_name: String,
+    member_src_desc: Option<String>,
+    dst_names: Vec<Option<String>>,
+}
+
+impl<W: std::fmt::Write> JsonLinesWriter<W> {
+    /// Creates a new JSON lines writer.
+    pub fn new(write: W) -> JsonLinesWriter<W> {
+        JsonLinesWriter {
+            write,
+            class_src_name: String::new(),
+            member_src_name: String::new(),
+            member_src_desc: None,
+            dst_names: Vec::new(),
+        }
+    }
+}
+
+impl<W: std::fmt::Write> MappingVisitor for JsonLinesWriter<W> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        HashSet::new()
+    }
+
+    fn supports_comments(&self) -> bool {
+        false
+    }
+
+    fn supports_metadata(&self) -> bool {
+        false
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.dst_names = vec![None; dst_namespaces.len()];
+        writeln!(self.write, "{}", json!({ "kind": "namespaces", "src": src_namespace, "dst": dst_namespaces }))?;
+        Ok(())
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_src_name = src_name.to_owned();
+        Ok(true)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member_src_name = src_name.to_owned();
+        self.member_src_desc = src_desc.map(str::to_owned);
+        Ok(true)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member_src_name = src_name.to_owned();
+        self.member_src_desc = src_desc.map(str::to_owned);
+        Ok(true)
+    }
+
+    fn visit_method_arg(&mut self, _arg_position: i32, _lv_index: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_method_var(&mut self, _lvt_row_index: i32, _lv_index: i32, _start_op_idx: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_dst_name(&mut self, _target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if let Some(dst_name) = self.dst_names.get_mut(namespace) {
+            *dst_name = Some(name.to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        let dst_names = self.dst_names.clone();
+        self.dst_names.fill(None);
+
+        let line = match target_kind {
+            MappedElementKind::Class => json!({ "kind": "class", "src": self.class_src_name, "dst": dst_names }),
+            MappedElementKind::Field | MappedElementKind::Method => json!({
+                "kind": if target_kind == MappedElementKind::Field { "field" } else { "method" },
+                "owner": self.class_src_name,
+                "src": self.member_src_name,
+                "desc": self.member_src_desc,
+                "dst": dst_names,
+            }),
+            _ => return Ok(false),
+        };
+
+        writeln!(self.write, "{}", line)?;
+        Ok(true)
+    }
+
+    fn visit_comment(&mut self, _target_kind: MappedElementKind, _comment: &str) -> VisitResult<()> {
+        Ok(())
+    }
+}
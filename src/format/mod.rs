@@ -14,4 +14,119 @@
  * limitations under the License.
 */
 
+pub mod csv;
+pub mod enigma;
+#[cfg(feature = "serde")]
+pub mod json_lines;
+pub mod proguard;
+pub mod recaf;
+pub mod srg;
 pub mod tiny2;
+
+use std::cell::Cell;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::rc::Rc;
+use anyhow::{anyhow, bail};
+
+/// The mapping formats supported by [`MappingTree::read`](crate::tree::MappingTree::read)
+/// and [`MappingTree::write`](crate::tree::MappingTree::write).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MappingFormat {
+    /// Tiny v2, read by [`tiny2::Tiny2Reader`] and written by [`tiny2::Tiny2Writer`].
+    Tiny2,
+    /// SRG, read by [`srg::SrgReader`] and written by [`srg::SrgWriter`].
+    Srg,
+    /// Single-file Enigma, read by [`enigma::EnigmaSingleFileReader`] and written by [`enigma::EnigmaSingleFileWriter`].
+    EnigmaSingleFile,
+    /// ProGuard, read by [`proguard::ProGuardReader`]. There is no writer for this format.
+    ProGuard,
+}
+
+/// A [`fmt::Write`] wrapper that counts the bytes written through it.
+///
+/// Writers take ownership of their output, so the count is read through a [`ByteCounter`]
+/// obtained using [`counter`][Self::counter] before the wrapper is passed to a writer.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt::Write;
+/// use jvm_obfuscation_mappings::format::CountingWrite;
+///
+/// let mut output = String::new();
+/// let mut write = CountingWrite::new(&mut output);
+/// let counter = write.counter();
+/// write.write_str("hello")?;
+/// write.write_char('ä')?;
+/// assert_eq!(counter.get(), 7);
+/// # Ok::<(), std::fmt::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct CountingWrite<W> {
+    inner: W,
+    counter: ByteCounter,
+}
+
+impl<W: fmt::Write> CountingWrite<W> {
+    /// Creates a new counting wrapper around `inner`.
+    pub fn new(inner: W) -> CountingWrite<W> {
+        CountingWrite { inner, counter: ByteCounter::default() }
+    }
+
+    /// Returns a handle to the byte count of this wrapper.
+    pub fn counter(&self) -> ByteCounter {
+        self.counter.clone()
+    }
+
+    /// Returns the wrapped output.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for CountingWrite<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_str(s)?;
+        self.counter.count.set(self.counter.count.get() + s.len());
+        Ok(())
+    }
+}
+
+/// A shared handle to the number of bytes written through a [`CountingWrite`].
+#[derive(Debug, Clone, Default)]
+pub struct ByteCounter {
+    count: Rc<Cell<usize>>,
+}
+
+impl ByteCounter {
+    /// Returns the number of bytes written so far.
+    pub fn get(&self) -> usize {
+        self.count.get()
+    }
+}
+
+/// Wraps an error from reading the line `line_number` (counting from 1) with the line number.
+///
+/// Invalid UTF-8 is reported as such, since [`io::BufRead::lines`] only reports it as invalid data.
+pub(crate) fn line_error(error: io::Error, line_number: usize) -> anyhow::Error {
+    let context = if error.kind() == io::ErrorKind::InvalidData {
+        format!("Invalid UTF-8 on line {}", line_number)
+    } else {
+        format!("Could not read line {}", line_number)
+    };
+
+    anyhow!(error).context(context)
+}
+
+/// Fails if `reader` has no input at all.
+///
+/// None of the supported formats consider a zero-byte file valid mappings, so empty input
+/// is reported instead of being read as an empty mapping set.
+pub(crate) fn check_not_empty<R: BufRead>(reader: &mut R, missing: &str) -> anyhow::Result<()> {
+    if reader.fill_buf().map_err(|error| line_error(error, 1))?.is_empty() {
+        bail!("Empty mapping file: missing {}", missing);
+    }
+
+    Ok(())
+}
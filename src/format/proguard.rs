@@ -0,0 +1,211 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::io::BufRead;
+use anyhow::{anyhow, bail, Context};
+use crate::MappedElementKind;
+use crate::descriptor::{ClassName, MethodDescriptor, Type};
+use crate::format::{check_not_empty, line_error};
+use crate::tree::MappingTree;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The source namespace used by [`ProGuardReader::read`].
+pub const PROGUARD_SRC_NAMESPACE: &str = "source";
+/// The destination namespace used by [`ProGuardReader::read`].
+pub const PROGUARD_DST_NAMESPACE: &str = "target";
+
+/// A reader for ProGuard and R8 mapping files.
+///
+/// The original names in the file are used as the source names, and the obfuscated names
+/// as the destination names. Descriptors are built from the Java types in the file,
+/// which use the original class names.
+///
+/// Method lines can carry line number ranges (`1:3:void foo():10:12 -> a`). The ranges are skipped,
+/// and so are the frames of methods inlined from other classes, which use qualified names.
+/// This metadata is currently discarded.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::proguard::ProGuardReader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let proguard = "com.example.Foo -> a:\n    \
+///                     java.lang.String name -> b\n    \
+///                     void setName(java.lang.String) -> c\n";
+/// let mut tree = MappingTree::new();
+/// ProGuardReader::read_str(proguard, &mut tree)?;
+///
+/// let class = tree.class("com/example/Foo").unwrap();
+/// assert_eq!(class.dst_name(0), Some("a"));
+/// assert_eq!(class.field("name", Some("Ljava/lang/String;")).unwrap().dst_name(0), Some("b"));
+/// assert_eq!(class.method("setName", Some("(Ljava/lang/String;)V")).unwrap().dst_name(0), Some("c"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ProGuardReader;
+
+impl ProGuardReader {
+    /// Reads ProGuard mappings from a string using the namespaces
+    /// [`PROGUARD_SRC_NAMESPACE`] and [`PROGUARD_DST_NAMESPACE`].
+    pub fn read_str<V: MappingVisitor + ?Sized>(input: &str, visitor: &mut V) -> anyhow::Result<()> {
+        ProGuardReader::read(input.as_bytes(), visitor)
+    }
+
+    /// Reads ProGuard mappings using the namespaces [`PROGUARD_SRC_NAMESPACE`] and [`PROGUARD_DST_NAMESPACE`].
+    ///
+    /// # Examples
+    ///
+    /// Line number and inlining ranges are skipped, so a method with inlined frames
+    /// is only visited once:
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::events::{EventSink, MappingEvent};
+    /// use jvm_obfuscation_mappings::format::proguard::ProGuardReader;
+    ///
+    /// let proguard = "com.example.Foo -> a:\n    \
+    ///                     1:1:void foo():42:42 -> a\n    \
+    ///                     1:1:void com.example.Bar.bar():7:7 -> a\n";
+    /// let mut sink = EventSink::new();
+    /// ProGuardReader::read(proguard.as_bytes(), &mut sink)?;
+    ///
+    /// let methods: Vec<_> = sink.events().iter()
+    ///     .filter(|event| matches!(event, MappingEvent::Method { .. }))
+    ///     .collect();
+    /// assert_eq!(methods, [&MappingEvent::Method { src_name: "foo".to_owned(), src_desc: Some("()V".to_owned()) }]);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn read<R: BufRead, V: MappingVisitor + ?Sized>(reader: R, visitor: &mut V) -> anyhow::Result<()> {
+        ProGuardReader::read_with_namespaces(reader, PROGUARD_SRC_NAMESPACE, PROGUARD_DST_NAMESPACE, visitor)
+    }
+
+    /// Reads ProGuard mappings using custom namespace names.
+    ///
+    /// A method can be listed several times with different line number ranges, so visitors
+    /// that need uniqueness or multiple passes are fed through an intermediate [`MappingTree`].
+    pub fn read_with_namespaces<R: BufRead, V: MappingVisitor + ?Sized>(
+        mut reader: R,
+        src_namespace: &str,
+        dst_namespace: &str,
+        visitor: &mut V,
+    ) -> anyhow::Result<()> {
+        check_not_empty(&mut reader, "class mappings")?;
+        let flags = visitor.flags();
+
+        if flags.contains(&MappingFlag::NeedsUniqueness) || flags.contains(&MappingFlag::NeedsMultiplePasses) {
+            let mut tree = MappingTree::new();
+            ProGuardReader::read_with_namespaces(reader, src_namespace, dst_namespace, &mut tree)?;
+            return tree.accept(visitor);
+        }
+
+        if visitor.visit_header()? {
+            visitor.visit_namespaces(src_namespace, &[dst_namespace])?;
+        }
+
+        if visitor.visit_content()? {
+            let mut visit_members = false;
+
+            for (line_number, line) in reader.lines().enumerate() {
+                let line = line.map_err(|error| line_error(error, line_number + 1))?;
+                visitor.visit_source_position(line_number + 1)?;
+                read_line(&line, visitor, &mut visit_members)
+                    .with_context(|| format!("Invalid ProGuard mapping on line {}", line_number + 1))?;
+            }
+        }
+
+        visitor.visit_end()?;
+        Ok(())
+    }
+}
+
+fn read_line<V: MappingVisitor + ?Sized>(line: &str, visitor: &mut V, visit_members: &mut bool) -> VisitResult<()> {
+    let trimmed = line.trim();
+
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return Ok(());
+    }
+
+    let (src, dst) = trimmed.split_once(" -> ").ok_or_else(|| anyhow!("Missing -> in line: {}", line))?;
+
+    if !line.starts_with(char::is_whitespace) {
+        let dst = dst.strip_suffix(':').ok_or_else(|| anyhow!("Class mapping doesn't end with a colon: {}", line))?;
+        let src = ClassName::from_binary_name(src);
+        let dst = ClassName::from_binary_name(dst);
+
+        *visit_members = false;
+
+        if visitor.visit_class(src.internal_name())? {
+            visitor.visit_dst_name(MappedElementKind::Class, 0, dst.internal_name())?;
+            *visit_members = visitor.visit_element_content(MappedElementKind::Class)?;
+        }
+
+        return Ok(());
+    }
+
+    if !*visit_members {
+        return Ok(());
+    }
+
+    let (kind, name, desc) = match src.find('(') {
+        Some(_) => match parse_method(src)? {
+            Some((name, desc)) => (MappedElementKind::Method, name, desc),
+            None => return Ok(()),
+        },
+        None => {
+            let (field_type, name) = src.split_once(' ').ok_or_else(|| anyhow!("Missing field type in line: {}", line))?;
+            (MappedElementKind::Field, name, Type::from_java_name(field_type)?.descriptor())
+        }
+    };
+
+    let visit_member = match kind {
+        MappedElementKind::Field => visitor.visit_field(name, Some(&desc))?,
+        _ => visitor.visit_method(name, Some(&desc))?,
+    };
+
+    if visit_member {
+        visitor.visit_dst_name(kind, 0, dst)?;
+        visitor.visit_element_content(kind)?;
+    }
+
+    Ok(())
+}
+
+/// Parses the source part of a method line into its name and descriptor.
+///
+/// Returns `None` for frames of methods inlined from other classes.
+fn parse_method(src: &str) -> anyhow::Result<Option<(&str, String)>> {
+    // TODO: Expose the line number and inlining metadata instead of discarding it.
+    // Skip the line number range before the return type.
+    let src = src.trim_start_matches(|c: char| c.is_ascii_digit() || c == ':');
+    let (return_type, rest) = src.split_once(' ').ok_or_else(|| anyhow!("Missing return type in method {}", src))?;
+    let (name, rest) = rest.split_once('(').ok_or_else(|| anyhow!("Missing parameter list in method {}", src))?;
+    // Everything after the parameter list is the original line number range.
+    let (params, _) = rest.split_once(')').ok_or_else(|| anyhow!("Unterminated parameter list in method {}", src))?;
+
+    if name.contains('.') {
+        return Ok(None);
+    }
+
+    if name.is_empty() {
+        bail!("Missing name in method {}", src);
+    }
+
+    let params = params.split(',')
+        .filter(|param| !param.is_empty())
+        .map(Type::from_java_name)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let desc = MethodDescriptor::new(params, Type::from_java_name(return_type)?);
+    Ok(Some((name, desc.descriptor())))
+}
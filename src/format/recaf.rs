@@ -0,0 +1,221 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::io::BufRead;
+use anyhow::{anyhow, bail, Context};
+use crate::MappedElementKind;
+use crate::format::{check_not_empty, line_error};
+use crate::tree::MappingTree;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The source namespace used by [`RecafSimpleReader::read`].
+pub const RECAF_SRC_NAMESPACE: &str = "source";
+/// The destination namespace used by [`RecafSimpleReader::read`].
+pub const RECAF_DST_NAMESPACE: &str = "target";
+
+/// A reader for Recaf's simple (flat) mapping format.
+///
+/// Each line maps a single element using internal names:
+///
+/// - `<class> <new name>` for classes
+/// - `<class>.<field> <new name>` or `<class>.<field> <descriptor> <new name>` for fields
+/// - `<class>.<method><descriptor> <new name>` for methods
+///
+/// Lines starting with `#` are comments. Tokens can be enclosed in double quotes to allow spaces in names.
+/// Quoted tokens support the escape sequences `\"`, `\\`, `\n`, `\r` and `\t`.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::recaf::RecafSimpleReader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let recaf = "a com/example/Foo\n\
+///              a.b I count\n\
+///              a.c(I)V setCount\n\
+///              \"b\" \"com/example/Some Class\"\n\
+///              \"d\" \"com/example/\\\"Quoted\\\"\"\n";
+/// let mut tree = MappingTree::new();
+/// RecafSimpleReader::read_str(recaf, &mut tree)?;
+///
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("com/example/Foo"));
+/// assert_eq!(class.field("b", Some("I")).unwrap().dst_name(0), Some("count"));
+/// assert_eq!(class.method("c", Some("(I)V")).unwrap().dst_name(0), Some("setCount"));
+/// assert_eq!(tree.class("b").unwrap().dst_name(0), Some("com/example/Some Class"));
+/// assert_eq!(tree.class("d").unwrap().dst_name(0), Some("com/example/\"Quoted\""));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct RecafSimpleReader;
+
+impl RecafSimpleReader {
+    /// Reads Recaf simple mappings from a string using the namespaces
+    /// [`RECAF_SRC_NAMESPACE`] and [`RECAF_DST_NAMESPACE`].
+    pub fn read_str<V: MappingVisitor + ?Sized>(input: &str, visitor: &mut V) -> anyhow::Result<()> {
+        RecafSimpleReader::read(input.as_bytes(), visitor)
+    }
+
+    /// Reads Recaf simple mappings using the namespaces [`RECAF_SRC_NAMESPACE`] and [`RECAF_DST_NAMESPACE`].
+    pub fn read<R: BufRead, V: MappingVisitor + ?Sized>(reader: R, visitor: &mut V) -> anyhow::Result<()> {
+        RecafSimpleReader::read_with_namespaces(reader, RECAF_SRC_NAMESPACE, RECAF_DST_NAMESPACE, visitor)
+    }
+
+    /// Reads Recaf simple mappings using custom namespace names.
+    ///
+    /// Members are listed separately from their classes, so visitors that need uniqueness
+    /// or multiple passes are fed through an intermediate [`MappingTree`].
+    pub fn read_with_namespaces<R: BufRead, V: MappingVisitor + ?Sized>(
+        mut reader: R,
+        src_namespace: &str,
+        dst_namespace: &str,
+        visitor: &mut V,
+    ) -> anyhow::Result<()> {
+        check_not_empty(&mut reader, "Recaf mappings")?;
+        let flags = visitor.flags();
+
+        if flags.contains(&MappingFlag::NeedsUniqueness) || flags.contains(&MappingFlag::NeedsMultiplePasses) {
+            let mut tree = MappingTree::new();
+            RecafSimpleReader::read_with_namespaces(reader, src_namespace, dst_namespace, &mut tree)?;
+            return tree.accept(visitor);
+        }
+
+        if visitor.visit_header()? {
+            visitor.visit_namespaces(src_namespace, &[dst_namespace])?;
+        }
+
+        if visitor.visit_content()? {
+            for (line_number, line) in reader.lines().enumerate() {
+                let line = line.map_err(|error| line_error(error, line_number + 1))?;
+                visitor.visit_source_position(line_number + 1)?;
+                read_line(&line, visitor).with_context(|| format!("Invalid Recaf mappings on line {}", line_number + 1))?;
+            }
+        }
+
+        visitor.visit_end()?;
+        Ok(())
+    }
+}
+
+fn read_line<V: MappingVisitor + ?Sized>(line: &str, visitor: &mut V) -> VisitResult<()> {
+    if line.trim_start().starts_with('#') {
+        return Ok(());
+    }
+
+    let tokens = tokenize(line)?;
+
+    match tokens.as_slice() {
+        [] => {}
+        [src, dst] => match src.find('(') {
+            Some(desc_start) => {
+                let (member, desc) = src.split_at(desc_start);
+                read_member(visitor, MappedElementKind::Method, member, Some(desc), dst)?;
+            }
+            None => match src.rsplit_once('.') {
+                Some(_) => read_member(visitor, MappedElementKind::Field, src, None, dst)?,
+                None => {
+                    if visitor.visit_class(src)? {
+                        visitor.visit_dst_name(MappedElementKind::Class, 0, dst)?;
+                        visitor.visit_element_content(MappedElementKind::Class)?;
+                    }
+                }
+            },
+        },
+        [src, desc, dst] => read_member(visitor, MappedElementKind::Field, src, Some(desc), dst)?,
+        _ => bail!("Unknown line: {}", line),
+    }
+
+    Ok(())
+}
+
+fn read_member<V: MappingVisitor + ?Sized>(
+    visitor: &mut V,
+    kind: MappedElementKind,
+    src: &str,
+    src_desc: Option<&str>,
+    dst_name: &str,
+) -> VisitResult<()> {
+    let (owner, src_name) = src.rsplit_once('.').ok_or_else(|| anyhow!("Member {} has no owner", src))?;
+
+    if !visitor.visit_class(owner)? || !visitor.visit_element_content(MappedElementKind::Class)? {
+        return Ok(());
+    }
+
+    let visit_member = match kind {
+        MappedElementKind::Field => visitor.visit_field(src_name, src_desc)?,
+        _ => visitor.visit_method(src_name, src_desc)?,
+    };
+
+    if visit_member {
+        visitor.visit_dst_name(kind, 0, dst_name)?;
+        visitor.visit_element_content(kind)?;
+    }
+
+    Ok(())
+}
+
+/// Splits a line into whitespace-separated tokens, unquoting tokens enclosed in double quotes.
+fn tokenize(line: &str) -> anyhow::Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let token = match chars.peek() {
+            None => break,
+            Some('"') => {
+                chars.next();
+                let mut token = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => token.push(match chars.next() {
+                            Some('"') => '"',
+                            Some('\\') => '\\',
+                            Some('n') => '\n',
+                            Some('r') => '\r',
+                            Some('t') => '\t',
+                            Some(other) => bail!("Unknown escape sequence \\{}", other),
+                            None => bail!("Unterminated escape sequence in {}", line),
+                        }),
+                        Some(c) => token.push(c),
+                        None => bail!("Unterminated quoted name in {}", line),
+                    }
+                }
+
+                if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+                    bail!("Missing whitespace after quoted name in {}", line);
+                }
+
+                token
+            }
+            Some(_) => {
+                let mut token = String::new();
+
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    token.push(c);
+                }
+
+                token
+            }
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
@@ -0,0 +1,370 @@
+/*
+ * Copyright (c) 2021-2022 FabricMC, 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use std::io::BufRead;
+use anyhow::{anyhow, bail, Context};
+use crate::MappedElementKind;
+use crate::format::{check_not_empty, line_error};
+use crate::tree::MappingTree;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The source namespace used by [`SrgReader::read`].
+pub const SRG_SRC_NAMESPACE: &str = "source";
+/// The destination namespace used by [`SrgReader::read`].
+pub const SRG_DST_NAMESPACE: &str = "target";
+/// The prefix of the metadata keys used for package mappings by [`SrgReader::read_with_packages`].
+///
+/// Each `PK: <from> <to>` line is visited as a metadata property with the key `pk:<from>` and the value `<to>`.
+pub const SRG_PACKAGE_METADATA_PREFIX: &str = "pk:";
+
+/// A reader for SRG and XSRG mappings.
+///
+/// Method names are read verbatim, so special methods like `<init>` and `<clinit>`
+/// are treated like any other method.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::srg::{SrgReader, SrgWriter};
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Writer;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let srg = "CL: a com/example/Foo\nMD: a/<init> (I)V com/example/Foo/<init> (I)V\n";
+/// let mut tree = MappingTree::new();
+/// SrgReader::read_str(srg, &mut tree)?;
+///
+/// let mut tiny = String::new();
+/// tree.accept(&mut Tiny2Writer::new(&mut tiny))?;
+/// assert!(tiny.contains("\tm\t(I)V\t<init>\t<init>\n"));
+///
+/// let mut written = String::new();
+/// tree.accept(&mut SrgWriter::new(&mut written))?;
+/// assert_eq!(written, srg);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct SrgReader;
+
+impl SrgReader {
+    /// Reads SRG mappings from a string using the namespaces
+    /// [`SRG_SRC_NAMESPACE`] and [`SRG_DST_NAMESPACE`].
+    pub fn read_str<V: MappingVisitor + ?Sized>(input: &str, visitor: &mut V) -> anyhow::Result<()> {
+        SrgReader::read(input.as_bytes(), visitor)
+    }
+
+    /// Reads SRG mappings using the namespaces [`SRG_SRC_NAMESPACE`] and [`SRG_DST_NAMESPACE`].
+    ///
+    /// # Examples
+    ///
+    /// Lines that aren't valid UTF-8 are reported with their line numbers:
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::srg::SrgReader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let srg: &[u8] = b"CL: a com/example/Foo\nCL: b com/example/Bar\nCL: c com/example/\xff\n";
+    /// let error = SrgReader::read(srg, &mut MappingTree::new()).unwrap_err();
+    /// assert_eq!(error.to_string(), "Invalid UTF-8 on line 3");
+    /// ```
+    pub fn read<R: BufRead, V: MappingVisitor + ?Sized>(reader: R, visitor: &mut V) -> anyhow::Result<()> {
+        SrgReader::read_with_namespaces(reader, SRG_SRC_NAMESPACE, SRG_DST_NAMESPACE, visitor)
+    }
+
+    /// Reads SRG mappings using custom namespace names.
+    ///
+    /// SRG files don't group members by their classes, so visitors that need uniqueness
+    /// or multiple passes are fed through an intermediate [`MappingTree`].
+    pub fn read_with_namespaces<R: BufRead, V: MappingVisitor + ?Sized>(
+        reader: R,
+        src_namespace: &str,
+        dst_namespace: &str,
+        visitor: &mut V,
+    ) -> anyhow::Result<()> {
+        SrgReader::read_impl(reader, src_namespace, dst_namespace, false, visitor)
+    }
+
+    /// Reads SRG mappings like [`read`][Self::read], but visits package mappings (`PK:` lines)
+    /// as metadata instead of ignoring them.
+    ///
+    /// The metadata keys start with [`SRG_PACKAGE_METADATA_PREFIX`]. The package mappings can be applied
+    /// to classes without an explicit mapping using [`PackageMappingApplier`](crate::adapter::PackageMappingApplier).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::srg::SrgReader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let mut tree = MappingTree::new();
+    /// SrgReader::read_with_packages("PK: com/old com/new\n".as_bytes(), &mut tree)?;
+    /// assert_eq!(tree.metadata(), &[("pk:com/old".to_owned(), "com/new".to_owned())]);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn read_with_packages<R: BufRead, V: MappingVisitor + ?Sized>(reader: R, visitor: &mut V) -> anyhow::Result<()> {
+        SrgReader::read_impl(reader, SRG_SRC_NAMESPACE, SRG_DST_NAMESPACE, true, visitor)
+    }
+
+    fn read_impl<R: BufRead, V: MappingVisitor + ?Sized>(
+        mut reader: R,
+        src_namespace: &str,
+        dst_namespace: &str,
+        read_packages: bool,
+        visitor: &mut V,
+    ) -> anyhow::Result<()> {
+        check_not_empty(&mut reader, "SRG entries")?;
+        let flags = visitor.flags();
+
+        if flags.contains(&MappingFlag::NeedsUniqueness) || flags.contains(&MappingFlag::NeedsMultiplePasses) {
+            let mut tree = MappingTree::new();
+            SrgReader::read_impl(reader, src_namespace, dst_namespace, read_packages, &mut tree)?;
+            return tree.accept(visitor);
+        }
+
+        if visitor.visit_header()? {
+            visitor.visit_namespaces(src_namespace, &[dst_namespace])?;
+        }
+
+        if visitor.visit_content()? {
+            for (line_number, line) in reader.lines().enumerate() {
+                let line = line.map_err(|error| line_error(error, line_number + 1))?;
+                visitor.visit_source_position(line_number + 1)?;
+                read_line(&line, read_packages, visitor).with_context(|| format!("Invalid SRG on line {}", line_number + 1))?;
+            }
+        }
+
+        visitor.visit_end()?;
+        Ok(())
+    }
+}
+
+/// Splits a member reference (`owner/name`) into its owner and name.
+fn split_member(member: &str) -> anyhow::Result<(&str, &str)> {
+    member.rsplit_once('/').ok_or_else(|| anyhow!("Member {} has no owner", member))
+}
+
+fn read_line<V: MappingVisitor + ?Sized>(line: &str, read_packages: bool, visitor: &mut V) -> VisitResult<()> {
+    let mut parts = line.split_whitespace();
+
+    let kind = match parts.next() {
+        Some(kind) => kind,
+        None => return Ok(()),
+    };
+    let args: Vec<&str> = parts.collect();
+
+    match (kind, args.as_slice()) {
+        ("PK:", [from, to]) => {
+            if read_packages {
+                visitor.visit_metadata(&format!("{}{}", SRG_PACKAGE_METADATA_PREFIX, from), to)?;
+            }
+        }
+        ("CL:", [src, dst]) => {
+            if visitor.visit_class(src)? {
+                visitor.visit_dst_name(MappedElementKind::Class, 0, dst)?;
+                visitor.visit_element_content(MappedElementKind::Class)?;
+            }
+        }
+        ("FD:", [src, dst]) => read_member(visitor, MappedElementKind::Field, src, None, dst, None)?,
+        ("FD:", [src, src_desc, dst, dst_desc]) => {
+            read_member(visitor, MappedElementKind::Field, src, Some(src_desc), dst, Some(dst_desc))?
+        }
+        ("MD:", [src, src_desc, dst, dst_desc]) => {
+            read_member(visitor, MappedElementKind::Method, src, Some(src_desc), dst, Some(dst_desc))?
+        }
+        _ => bail!("Unknown line: {}", line),
+    }
+
+    Ok(())
+}
+
+fn read_member<V: MappingVisitor + ?Sized>(
+    visitor: &mut V,
+    kind: MappedElementKind,
+    src: &str,
+    src_desc: Option<&str>,
+    dst: &str,
+    dst_desc: Option<&str>,
+) -> VisitResult<()> {
+    let (owner, src_name) = split_member(src)?;
+    let (dst_owner, dst_name) = split_member(dst)?;
+
+    if !visitor.visit_class(owner)? {
+        return Ok(());
+    }
+
+    // Owners that keep their name aren't mapped, so they're left to CL lines and package mappings.
+    if dst_owner != owner {
+        visitor.visit_dst_name(MappedElementKind::Class, 0, dst_owner)?;
+    }
+
+    if !visitor.visit_element_content(MappedElementKind::Class)? {
+        return Ok(());
+    }
+
+    let visit_member = match kind {
+        MappedElementKind::Field => visitor.visit_field(src_name, src_desc)?,
+        _ => visitor.visit_method(src_name, src_desc)?,
+    };
+
+    if visit_member {
+        visitor.visit_dst_name(kind, 0, dst_name)?;
+
+        if let Some(dst_desc) = dst_desc {
+            visitor.visit_dst_desc(kind, 0, dst_desc)?;
+        }
+
+        visitor.visit_element_content(kind)?;
+    }
+
+    Ok(())
+}
+
+/// An SRG writer as a [`MappingVisitor`] that outputs to [`std::fmt::Write`].
+///
+/// Only the first destination namespace is written. Members without a destination name
+/// keep their source name, and comments, arguments and variables are dropped.
+/// Each class mapping is written once, even if the class is visited again for each of its members.
+pub struct SrgWriter<W: std::fmt::Write> {
+    write: W,
+    written_classes: HashSet<String>,
+    class_src_name: String,
+    class_dst_name: Option<String>,
+    member_src_name: String,
+    member_src_desc: Option<String>,
+    dst_name: Option<String>,
+    dst_desc: Option<String>,
+}
+
+impl<W: std::fmt::Write> SrgWriter<W> {
+    /// Creates a new SRG writer.
+    pub fn new(write: W) -> SrgWriter<W> {
+        SrgWriter {
+            write,
+            written_classes: HashSet::new(),
+            class_src_name: String::new(),
+            class_dst_name: None,
+            member_src_name: String::new(),
+            member_src_desc: None,
+            dst_name: None,
+            dst_desc: None,
+        }
+    }
+}
+
+impl<W: std::fmt::Write> MappingVisitor for SrgWriter<W> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        HashSet::from([MappingFlag::NeedsSrcMethodDesc, MappingFlag::NeedsDstMethodDesc])
+    }
+
+    fn supports_comments(&self) -> bool {
+        false
+    }
+
+    fn supports_metadata(&self) -> bool {
+        false
+    }
+
+    fn visit_namespaces(&mut self, _src_namespace: &str, _dst_namespaces: &[&str]) -> VisitResult<()> {
+        Ok(())
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_src_name = src_name.to_owned();
+        self.class_dst_name = None;
+        self.dst_name = None;
+        Ok(true)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member_src_name = src_name.to_owned();
+        self.member_src_desc = src_desc.map(str::to_owned);
+        self.dst_name = None;
+        self.dst_desc = None;
+        Ok(true)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.member_src_name = src_name.to_owned();
+        self.member_src_desc = src_desc.map(str::to_owned);
+        self.dst_name = None;
+        self.dst_desc = None;
+        Ok(true)
+    }
+
+    fn visit_method_arg(&mut self, _arg_position: i32, _lv_index: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_method_var(&mut self, _lvt_row_index: i32, _lv_index: i32, _start_op_idx: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_dst_name(&mut self, _target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if namespace == 0 {
+            self.dst_name = Some(name.to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn visit_dst_desc(&mut self, _target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        if namespace == 0 {
+            self.dst_desc = Some(desc.to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        let dst_name = self.dst_name.take();
+
+        match target_kind {
+            MappedElementKind::Class => {
+                self.class_dst_name = dst_name;
+
+                if let Some(dst_name) = &self.class_dst_name {
+                    if self.written_classes.insert(self.class_src_name.clone()) {
+                        writeln!(self.write, "CL: {} {}", self.class_src_name, dst_name)?;
+                    }
+                }
+            }
+            MappedElementKind::Field => {
+                let class_dst_name = self.class_dst_name.as_deref().unwrap_or(&self.class_src_name);
+                let dst_name = dst_name.as_deref().unwrap_or(&self.member_src_name);
+                writeln!(
+                    self.write, "FD: {}/{} {}/{}",
+                    self.class_src_name, self.member_src_name, class_dst_name, dst_name
+                )?;
+            }
+            MappedElementKind::Method => {
+                let class_dst_name = self.class_dst_name.as_deref().unwrap_or(&self.class_src_name);
+                let src_desc = self.member_src_desc.as_deref().ok_or_else(|| anyhow!("SrgWriter needs src desc!"))?;
+                let dst_desc = self.dst_desc.as_deref().ok_or_else(|| anyhow!("SrgWriter needs dst desc!"))?;
+                let dst_name = dst_name.as_deref().unwrap_or(&self.member_src_name);
+                writeln!(
+                    self.write, "MD: {}/{} {} {}/{} {}",
+                    self.class_src_name, self.member_src_name, src_desc, class_dst_name, dst_name, dst_desc
+                )?;
+            }
+            _ => {}
+        }
+
+        Ok(true)
+    }
+
+    fn visit_comment(&mut self, _target_kind: MappedElementKind, _comment: &str) -> VisitResult<()> {
+        Ok(())
+    }
+}
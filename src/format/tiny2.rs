@@ -15,21 +15,734 @@
 */
 
 use std::collections::HashSet;
-use anyhow::anyhow;
+use std::io::BufRead;
+use anyhow::{anyhow, bail, Context};
 use crate::MappedElementKind;
+use crate::format::{check_not_empty, line_error};
+use crate::tree::{ClassEntry, MappingTree};
 use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
 
-// TODO: escape_names
+/// A reader for Tiny v2 mappings.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let tiny = "tiny\tv2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tm\t(I)V\tb\tsetValue\n\
+///             \t\tp\t1\t\tvalue\n";
+/// let mut tree = MappingTree::new();
+/// Tiny2Reader::read_str(tiny, &mut tree)?;
+///
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("com/example/Foo"));
+/// let method = class.method("b", Some("(I)V")).unwrap();
+/// assert_eq!(method.dst_name(0), Some("setValue"));
+/// assert_eq!(method.args()[0].dst_name(0), Some("value"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct Tiny2Reader;
+
+impl Tiny2Reader {
+    /// Reads Tiny v2 mappings from a string.
+    pub fn read_str<V: MappingVisitor + ?Sized>(input: &str, visitor: &mut V) -> anyhow::Result<()> {
+        Tiny2Reader::read(input.as_bytes(), visitor)
+    }
+
+    /// Reads Tiny v2 mappings.
+    ///
+    /// Both `2` and `v2` are accepted as the major version in the header.
+    /// Visitors that need multiple passes are fed through an intermediate [`MappingTree`].
+    ///
+    /// If the `escaped-names` property is present, names and comments are unescaped.
+    /// Otherwise comments are read verbatim to the end of the line.
+    ///
+    /// Trailing destination names may be omitted from element lines, in which case the element
+    /// has no names in those namespaces. Lines with more destination names than namespaces are rejected.
+    ///
+    /// The `dst-desc-namespaces` property lists destination namespaces, separated by spaces,
+    /// whose field and method columns contain destination descriptors instead of names.
+    /// They are passed on using [`visit_dst_desc`](MappingVisitor::visit_dst_desc).
+    /// Since a [`MappingTree`] doesn't store destination descriptors, they are lost for
+    /// visitors that need multiple passes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+    ///             \tescaped-names\n\
+    ///             c\ta\tcom/example/Foo\n\
+    ///             \tc\tFirst\\tSecond\n";
+    /// let mut tree = MappingTree::new();
+    /// Tiny2Reader::read(tiny.as_bytes(), &mut tree)?;
+    /// assert_eq!(tree.class("a").unwrap().comment(), Some("First\tSecond"));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// Omitted destination names:
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tintermediary\tnamed\n\
+    ///             c\ta\tclass_1\tcom/example/Foo\n\
+    ///             \tf\tI\tb\tfield_1\n\
+    ///             \tm\t()V\tc\tmethod_1\trun\n";
+    /// let mut tree = MappingTree::new();
+    /// Tiny2Reader::read(tiny.as_bytes(), &mut tree)?;
+    ///
+    /// let class = tree.class("a").unwrap();
+    /// assert_eq!(class.fields()[0].dst_name(0), Some("field_1"));
+    /// assert_eq!(class.fields()[0].dst_name(1), None);
+    /// assert_eq!(class.methods()[0].dst_name(1), Some("run"));
+    ///
+    /// let too_many = "tiny\t2\t0\tofficial\tnamed\nc\ta\tb\tc\n";
+    /// assert!(Tiny2Reader::read(too_many.as_bytes(), &mut MappingTree::new()).is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// Destination descriptors:
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::MappedElementKind;
+    /// use jvm_obfuscation_mappings::events::{EventSink, MappingEvent};
+    /// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tnamed\tnamed_desc\n\
+    ///             \tdst-desc-namespaces\tnamed_desc\n\
+    ///             c\ta\tcom/example/Foo\n\
+    ///             \tm\t(La;)V\tb\trun\t(Lcom/example/Foo;)V\n";
+    /// let mut sink = EventSink::new();
+    /// Tiny2Reader::read(tiny.as_bytes(), &mut sink)?;
+    ///
+    /// assert!(sink.events().contains(&MappingEvent::DstDesc {
+    ///     target_kind: MappedElementKind::Method,
+    ///     namespace: 1,
+    ///     desc: "(Lcom/example/Foo;)V".to_owned(),
+    /// }));
+    /// assert!(!sink.events().iter().any(|event| matches!(event, MappingEvent::DstName { namespace: 1, .. })));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn read<R: BufRead, V: MappingVisitor + ?Sized>(reader: R, visitor: &mut V) -> anyhow::Result<()> {
+        Tiny2Reader::read_with_minor_version(reader, visitor)?;
+        Ok(())
+    }
+
+    /// Reads Tiny v2 mappings into a new [`MappingTree`] that shares the storage of equal member descriptors.
+    ///
+    /// The resulting tree is equal to one filled by [`read`][Self::read]. Large mappings repeat
+    /// a small set of descriptors across most of their members, so sharing them saves allocations
+    /// and memory. Only descriptors are interned: namespace names are stored once per tree anyway,
+    /// and class names are stored whole, so their package prefixes aren't shared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+    ///             c\ta\tcom/example/Foo\n\
+    ///             \tf\tI\tb\tcount\n\
+    ///             \tf\tI\tc\tsize\n";
+    /// let interned = Tiny2Reader::read_interned(tiny.as_bytes())?;
+    ///
+    /// let mut tree = MappingTree::new();
+    /// Tiny2Reader::read_str(tiny, &mut tree)?;
+    /// assert_eq!(interned, tree);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn read_interned<R: BufRead>(reader: R) -> anyhow::Result<MappingTree> {
+        let mut tree = MappingTree::with_interned_descs();
+        Tiny2Reader::read(reader, &mut tree)?;
+        Ok(tree)
+    }
+
+    /// Reads Tiny v2 mappings like [`read`][Self::read], and returns the minor version from the header.
+    ///
+    /// The third header column is accepted as is, since some producers store other values there.
+    /// It can be reproduced in the output using [`Tiny2Writer::with_minor_version`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let mut tree = MappingTree::new();
+    /// let minor_version = Tiny2Reader::read_with_minor_version("tiny\t2\t0\tofficial\tnamed\n".as_bytes(), &mut tree)?;
+    /// assert_eq!(minor_version, "0");
+    ///
+    /// let tiny = "tiny\tv2\t3\tofficial\tnamed\nc\ta\tcom/example/Foo\n";
+    /// let minor_version = Tiny2Reader::read_with_minor_version(tiny.as_bytes(), &mut tree)?;
+    /// assert_eq!(minor_version, "3");
+    ///
+    /// let mut output = String::new();
+    /// tree.accept(&mut Tiny2Writer::new(&mut output).with_minor_version(&minor_version))?;
+    /// assert_eq!(output, tiny);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn read_with_minor_version<R: BufRead, V: MappingVisitor + ?Sized>(mut reader: R, visitor: &mut V) -> anyhow::Result<String> {
+        check_not_empty(&mut reader, "Tiny v2 header")?;
+
+        if visitor.flags().contains(&MappingFlag::NeedsMultiplePasses) {
+            let mut tree = MappingTree::new();
+            let minor_version = Tiny2Reader::read_with_minor_version(reader, &mut tree)?;
+            tree.accept(visitor)?;
+            return Ok(minor_version);
+        }
+
+        let mut lines = reader.lines();
+        let header = lines.next().ok_or_else(|| anyhow!("Missing Tiny v2 header"))?
+            .map_err(|error| line_error(error, 1))?;
+        let columns = header_columns(&header)?;
+        let src_namespace = columns[3];
+        let dst_namespaces = &columns[4..];
+        let visit_header = visitor.visit_header()?;
+
+        if visit_header {
+            visitor.visit_namespaces(src_namespace, dst_namespaces)?;
+        }
+
+        let mut state = ReadState {
+            dst_namespaces: dst_namespaces.iter().map(|&namespace| namespace.to_owned()).collect(),
+            desc_namespaces: HashSet::new(),
+            escaped_names: false,
+            visit_content: None,
+            skip_level: None,
+            element_kinds: [None; 3],
+        };
+
+        for (line_number, line) in lines.enumerate() {
+            let line = line.map_err(|error| line_error(error, line_number + 2))?;
+            visitor.visit_source_position(line_number + 2)?;
+
+            if !state.read_line(&line, visit_header, visitor)
+                .with_context(|| format!("Invalid Tiny v2 on line {}", line_number + 2))? {
+                break;
+            }
+        }
+
+        if state.visit_content.is_none() {
+            visitor.visit_content()?;
+        }
+
+        visitor.visit_end()?;
+        Ok(columns[2].to_owned())
+    }
+}
+
+/// Splits a Tiny v2 header into its columns, checking that it's valid.
+fn header_columns(header: &str) -> anyhow::Result<Vec<&str>> {
+    let columns: Vec<&str> = header.split('\t').collect();
+
+    match columns.as_slice() {
+        ["tiny", "2" | "v2", _, _, ..] => Ok(columns),
+        _ => bail!("Invalid Tiny v2 header: {}", header),
+    }
+}
+
+/// An iterator that reads Tiny v2 mappings lazily, one class at a time.
+///
+/// The header and its properties are read up front by [`new`][Self::new]. Each call to
+/// [`next`][Iterator::next] then reads the lines of a single class with all of its members
+/// and yields it as a [`ClassEntry`], so only one class is kept in memory at a time.
+/// After an error is yielded, the iterator ends.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Iterator;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tf\tI\tb\tcount\n\
+///             c\tc\tcom/example/Bar\n\
+///             \tm\t(I)V\td\tsetValue\n\
+///             \t\tp\t1\t\tvalue\n\
+///             c\te\tcom/example/Baz\n";
+/// let iterator = Tiny2Iterator::new(tiny.as_bytes())?;
+/// assert_eq!(iterator.src_namespace(), "official");
+/// assert_eq!(iterator.dst_namespaces(), &["named"]);
+///
+/// let classes = iterator.collect::<anyhow::Result<Vec<_>>>()?;
+/// assert_eq!(classes.len(), 3);
+/// assert_eq!(classes[0].field("b", Some("I")).unwrap().dst_name(0), Some("count"));
+/// assert_eq!(classes[1].methods()[0].args()[0].dst_name(0), Some("value"));
+/// assert_eq!(classes[2].dst_name(0), Some("com/example/Baz"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct Tiny2Iterator<R: BufRead> {
+    lines: std::io::Lines<R>,
+    line_number: usize,
+    minor_version: String,
+    header: MappingTree,
+    state: ReadState,
+    /// The first line of the next class, which was read while looking for the end of the previous one.
+    next_line: Option<String>,
+    done: bool,
+}
+
+impl<R: BufRead> Tiny2Iterator<R> {
+    /// Creates a new iterator, reading the header and its properties from `reader`.
+    pub fn new(mut reader: R) -> anyhow::Result<Tiny2Iterator<R>> {
+        check_not_empty(&mut reader, "Tiny v2 header")?;
+
+        let mut lines = reader.lines();
+        let header = lines.next().ok_or_else(|| anyhow!("Missing Tiny v2 header"))?
+            .map_err(|error| line_error(error, 1))?;
+        let columns = header_columns(&header)?;
+        let dst_namespaces = &columns[4..];
+
+        let mut header_tree = MappingTree::new();
+        header_tree.visit_namespaces(columns[3], dst_namespaces)?;
+
+        let mut iterator = Tiny2Iterator {
+            lines,
+            line_number: 1,
+            minor_version: columns[2].to_owned(),
+            header: MappingTree::new(),
+            state: ReadState {
+                dst_namespaces: dst_namespaces.iter().map(|&namespace| namespace.to_owned()).collect(),
+                desc_namespaces: HashSet::new(),
+                escaped_names: false,
+                visit_content: None,
+                skip_level: None,
+                element_kinds: [None; 3],
+            },
+            next_line: None,
+            done: false,
+        };
+
+        while let Some(line) = iterator.next_line()? {
+            if !line.starts_with('\t') {
+                iterator.next_line = Some(line);
+                break;
+            }
+
+            iterator.state.read_line(&line, true, &mut header_tree)
+                .with_context(|| format!("Invalid Tiny v2 on line {}", iterator.line_number))?;
+        }
+
+        iterator.state.visit_content = Some(true);
+        iterator.header = header_tree;
+        Ok(iterator)
+    }
+
+    /// Returns the source namespace from the header.
+    pub fn src_namespace(&self) -> &str {
+        self.header.src_namespace().unwrap_or("")
+    }
+
+    /// Returns the destination namespaces from the header.
+    pub fn dst_namespaces(&self) -> &[String] {
+        self.header.dst_namespaces()
+    }
+
+    /// Returns the minor version from the header.
+    pub fn minor_version(&self) -> &str {
+        &self.minor_version
+    }
+
+    /// Returns the properties from the header as key-value pairs.
+    pub fn metadata(&self) -> &[(String, String)] {
+        self.header.metadata()
+    }
+
+    /// Reads the next line, returning `None` at the end of the input.
+    fn next_line(&mut self) -> anyhow::Result<Option<String>> {
+        match self.lines.next() {
+            Some(line) => {
+                self.line_number += 1;
+                Ok(Some(line.map_err(|error| line_error(error, self.line_number))?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the lines of the next class into a new tree, returning the class if there was one.
+    fn read_class(&mut self) -> anyhow::Result<Option<ClassEntry>> {
+        let mut tree = MappingTree::new();
+        let dst_namespaces: Vec<&str> = self.header.dst_namespaces().iter().map(String::as_str).collect();
+        tree.visit_namespaces(self.src_namespace(), &dst_namespaces)?;
+
+        let mut line = self.next_line.take();
+        let mut has_class = false;
+
+        if line.is_none() {
+            line = self.next_line()?;
+        }
+
+        while let Some(current) = line {
+            if !current.is_empty() && !current.starts_with('\t') {
+                if has_class {
+                    self.next_line = Some(current);
+                    break;
+                }
+
+                has_class = true;
+            }
+
+            self.state.read_line(&current, true, &mut tree)
+                .with_context(|| format!("Invalid Tiny v2 on line {}", self.line_number))?;
+            line = self.next_line()?;
+        }
+
+        tree.visit_end()?;
+        Ok(tree.into_classes().into_iter().next())
+    }
+}
+
+impl<R: BufRead> Iterator for Tiny2Iterator<R> {
+    type Item = anyhow::Result<ClassEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.read_class().transpose();
+
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+
+        result
+    }
+}
+
+/// The state of a [`Tiny2Reader`] while reading the lines after the header.
+struct ReadState {
+    dst_namespaces: Vec<String>,
+    /// The indices of the destination namespaces whose member columns contain descriptors.
+    desc_namespaces: HashSet<usize>,
+    escaped_names: bool,
+    /// The result of `visit_content`, or `None` if the content hasn't been reached yet.
+    visit_content: Option<bool>,
+    /// The indentation level of the element whose children are being skipped.
+    skip_level: Option<usize>,
+    /// The kinds of the current elements at each indentation level.
+    element_kinds: [Option<MappedElementKind>; 3],
+}
+
+impl ReadState {
+    /// Reads a single line, returning whether reading should continue.
+    fn read_line<V: MappingVisitor + ?Sized>(&mut self, line: &str, visit_header: bool, visitor: &mut V) -> anyhow::Result<bool> {
+        let indent = line.bytes().take_while(|&b| b == b'\t').count();
+        let mut columns = line[indent..].split('\t');
+        let section = columns.next().unwrap_or("");
+
+        if self.visit_content.is_none() {
+            if indent == 1 {
+                let value = columns.next().unwrap_or("");
+
+                if section == "escaped-names" {
+                    self.escaped_names = true;
+                } else if section == "dst-desc-namespaces" {
+                    for name in value.split_whitespace() {
+                        let namespace = self.dst_namespaces.iter().position(|namespace| namespace == name)
+                            .ok_or_else(|| anyhow!("Unknown destination namespace {}", name))?;
+                        self.desc_namespaces.insert(namespace);
+                    }
+                }
+
+                if visit_header {
+                    visitor.visit_metadata(section, value)?;
+                }
+
+                return Ok(true);
+            }
+
+            self.visit_content = Some(visitor.visit_content()?);
+        }
+
+        if self.visit_content == Some(false) {
+            return Ok(false);
+        }
+
+        if line.is_empty() {
+            return Ok(true);
+        }
+
+        match self.skip_level {
+            Some(level) if indent > level => return Ok(true),
+            _ => self.skip_level = None,
+        }
+
+        let columns: Vec<&str> = columns.collect();
+
+        match (indent, section, columns.as_slice()) {
+            (0, "c", [src_name, dst_names @ ..]) => {
+                let visit = visitor.visit_class(&self.unescape(src_name)?)?;
+                self.visit_element(visitor, indent, MappedElementKind::Class, visit, dst_names)?;
+            }
+            (1, "f", [src_desc, src_name, dst_names @ ..]) => {
+                let visit = visitor.visit_field(&self.unescape(src_name)?, Some(&self.unescape(src_desc)?))?;
+                self.visit_element(visitor, indent, MappedElementKind::Field, visit, dst_names)?;
+            }
+            (1, "m", [src_desc, src_name, dst_names @ ..]) => {
+                let visit = visitor.visit_method(&self.unescape(src_name)?, Some(&self.unescape(src_desc)?))?;
+                self.visit_element(visitor, indent, MappedElementKind::Method, visit, dst_names)?;
+            }
+            (2, "p", [lv_index, src_name, dst_names @ ..]) => {
+                let lv_index = lv_index.parse().with_context(|| format!("Invalid local variable index {}", lv_index))?;
+                let src_name = self.unescape(src_name)?;
+                let src_name = Some(src_name.as_str()).filter(|name| !name.is_empty());
+                let visit = visitor.visit_method_arg(-1, lv_index, src_name)?;
+                self.visit_element(visitor, indent, MappedElementKind::MethodArg, visit, dst_names)?;
+            }
+            (2, "v", [lv_index, start_op_idx, lvt_row_index, src_name, dst_names @ ..]) => {
+                let lv_index = lv_index.parse().with_context(|| format!("Invalid local variable index {}", lv_index))?;
+                let start_op_idx = start_op_idx.parse().with_context(|| format!("Invalid start opcode index {}", start_op_idx))?;
+                let lvt_row_index = lvt_row_index.parse().with_context(|| format!("Invalid LVT row index {}", lvt_row_index))?;
+                let src_name = self.unescape(src_name)?;
+                let src_name = Some(src_name.as_str()).filter(|name| !name.is_empty());
+                let visit = visitor.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)?;
+                self.visit_element(visitor, indent, MappedElementKind::MethodVar, visit, dst_names)?;
+            }
+            (1..=3, "c", [_, ..]) => {
+                let target_kind = self.element_kinds[indent - 1]
+                    .ok_or_else(|| anyhow!("Comment without an element"))?;
+                // Unescaped comments may contain tabs, so they extend to the end of the line.
+                let comment = &line[indent + 2..];
+                visitor.visit_comment(target_kind, &self.unescape(comment)?)?;
+            }
+            _ => bail!("Unknown line: {}", line),
+        }
+
+        Ok(true)
+    }
+
+    /// Visits the destination names and the element content of an element
+    /// if `visit` (the result of visiting the element itself) is true.
+    fn visit_element<V: MappingVisitor + ?Sized>(
+        &mut self,
+        visitor: &mut V,
+        indent: usize,
+        target_kind: MappedElementKind,
+        visit: bool,
+        dst_names: &[&str],
+    ) -> anyhow::Result<()> {
+        self.element_kinds[indent] = Some(target_kind);
+
+        if !visit {
+            self.skip_level = Some(indent);
+            return Ok(());
+        }
+
+        if dst_names.len() > self.dst_namespaces.len() {
+            bail!("Expected at most {} destination names, found {}", self.dst_namespaces.len(), dst_names.len());
+        }
+
+        let is_member = matches!(target_kind, MappedElementKind::Field | MappedElementKind::Method);
+
+        for (namespace, name) in dst_names.iter().enumerate() {
+            if name.is_empty() {
+                continue;
+            }
+
+            if is_member && self.desc_namespaces.contains(&namespace) {
+                visitor.visit_dst_desc(target_kind, namespace, &self.unescape(name)?)?;
+            } else {
+                visitor.visit_dst_name(target_kind, namespace, &self.unescape(name)?)?;
+            }
+        }
+
+        if !visitor.visit_element_content(target_kind)? {
+            self.skip_level = Some(indent);
+        }
+
+        Ok(())
+    }
+
+    /// Unescapes a name or a comment if the `escaped-names` property is present.
+    fn unescape(&self, name: &str) -> anyhow::Result<String> {
+        if self.escaped_names {
+            unescape(name)
+        } else {
+            Ok(name.to_owned())
+        }
+    }
+}
+
+/// Unescapes a Tiny v2 string.
+fn unescape(s: &str) -> anyhow::Result<String> {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        result.push(match chars.next() {
+            Some('\\') => '\\',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some(other) => bail!("Unknown escape sequence \\{}", other),
+            None => bail!("Unterminated escape sequence in {}", s),
+        });
+    }
+
+    Ok(result)
+}
+
+/// Escapes a Tiny v2 string, reversing [`unescape`].
+fn escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\0' => result.push_str("\\0"),
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
 /// A Tiny v2 writer as a [`MappingVisitor`] that outputs to [`std::fmt::Write`].
+///
+/// Namespace names that are empty or contain tabs or line breaks can't be read back,
+/// so they result in an error before the header is written.
+///
+/// Names and comments are escaped if the `escaped-names` property is written, either because
+/// it's enabled with [`with_escaped_names`][Self::with_escaped_names] or because it's passed in
+/// as metadata. Otherwise, names and comments containing tabs or line breaks result in an error.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Writer;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut output = String::new();
+/// let mut writer = Tiny2Writer::new(&mut output);
+/// assert!(writer.visit_namespaces("official", &[""]).is_err());
+/// assert!(writer.visit_namespaces("official", &["na\tmed"]).is_err());
+/// writer.visit_namespaces("official", &["named"])?;
+/// assert_eq!(output, "tiny\tv2\t0\tofficial\tnamed\n");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub struct Tiny2Writer<W: std::fmt::Write> {
     write: W,
+    minor_version: String,
+    trailing_newline: bool,
+    pending_newline: bool,
     dst_names: Vec<Option<String>>,
+    escaped_names: bool,
 }
 
 impl<W: std::fmt::Write> Tiny2Writer<W> {
     /// Creates a new Tiny v2 writer.
     pub fn new(write: W) -> Tiny2Writer<W> {
-        Tiny2Writer { write, dst_names: Vec::new(), }
+        Tiny2Writer {
+            write,
+            minor_version: "0".to_owned(),
+            trailing_newline: true,
+            pending_newline: false,
+            dst_names: Vec::new(),
+            escaped_names: false,
+        }
+    }
+
+    /// Sets the minor version written in the header, which is `0` by default.
+    pub fn with_minor_version(mut self, minor_version: &str) -> Self {
+        self.minor_version = minor_version.to_owned();
+        self
+    }
+
+    /// Sets whether the last line of the output ends with a newline, which is `true` by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tnamed\nc\ta\tFoo\n";
+    ///
+    /// let mut output = String::new();
+    /// Tiny2Reader::read_str(tiny, &mut Tiny2Writer::new(&mut output))?;
+    /// assert_eq!(output.as_bytes().last(), Some(&b'\n'));
+    ///
+    /// let mut output = String::new();
+    /// Tiny2Reader::read_str(tiny, &mut Tiny2Writer::new(&mut output).with_trailing_newline(false))?;
+    /// assert_eq!(output, "tiny\tv2\t0\tofficial\tnamed\nc\ta\tFoo");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// Sets whether the `escaped-names` property is written and names and comments are escaped,
+    /// which is `false` by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::MappedElementKind;
+    /// use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    /// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tnamed\n\tescaped-names\nc\ta\tFoo\n\tc\tFirst\\nSecond\n";
+    /// let mut tree = MappingTree::new();
+    /// Tiny2Reader::read_str(tiny, &mut tree)?;
+    /// assert_eq!(tree.class("a").unwrap().comment(), Some("First\nSecond"));
+    ///
+    /// let mut output = String::new();
+    /// tree.accept(&mut Tiny2Writer::new(&mut output).with_escaped_names(true))?;
+    /// assert_eq!(output, "tiny\tv2\t0\tofficial\tnamed\n\tescaped-names\nc\ta\tFoo\n\tc\tFirst\\nSecond\n");
+    ///
+    /// // The property is passed on from the tree's metadata.
+    /// let mut passed_on = String::new();
+    /// tree.accept(&mut Tiny2Writer::new(&mut passed_on))?;
+    /// assert_eq!(passed_on, output);
+    ///
+    /// let mut writer = Tiny2Writer::new(String::new());
+    /// writer.visit_namespaces("official", &["named"])?;
+    /// writer.visit_class("a")?;
+    /// writer.visit_element_content(MappedElementKind::Class)?;
+    /// assert!(writer.visit_comment(MappedElementKind::Class, "First\nSecond").is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_escaped_names(mut self, escaped_names: bool) -> Self {
+        self.escaped_names = escaped_names;
+        self
+    }
+
+    /// Writes a name or a comment, escaping it if the `escaped-names` property is written.
+    fn write_escaped(&mut self, s: &str) -> VisitResult<()> {
+        if self.escaped_names {
+            self.write.write_str(&escape(s))?;
+        } else if s.contains(['\t', '\n', '\r']) {
+            bail!("Tiny v2 name or comment {:?} contains a tab or a line break, which needs escaped names", s);
+        } else {
+            self.write.write_str(s)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_escaped_names_property(&mut self) -> VisitResult<()> {
+        self.start_line()?;
+        self.write.write_str("\tescaped-names")?;
+        self.writeln()
     }
 
     fn write_tab(&mut self) -> VisitResult<()> {
@@ -38,7 +751,22 @@ impl<W: std::fmt::Write> Tiny2Writer<W> {
     }
 
     fn writeln(&mut self) -> VisitResult<()> {
-        self.write.write_char('\n')?;
+        if self.trailing_newline {
+            self.write.write_char('\n')?;
+        } else {
+            // Without a trailing newline, the line is only terminated once the next one starts.
+            self.pending_newline = true;
+        }
+
+        Ok(())
+    }
+
+    fn start_line(&mut self) -> VisitResult<()> {
+        if self.pending_newline {
+            self.write.write_char('\n')?;
+            self.pending_newline = false;
+        }
+
         Ok(())
     }
 }
@@ -51,9 +779,19 @@ impl<W: std::fmt::Write> MappingVisitor for Tiny2Writer<W> {
     }
 
     fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        for namespace in std::iter::once(&src_namespace).chain(dst_namespaces) {
+            if namespace.is_empty() {
+                bail!("Tiny v2 namespace names can't be empty");
+            }
+
+            if namespace.contains(['\t', '\n', '\r']) {
+                bail!("Tiny v2 namespace name {:?} contains a tab or a line break", namespace);
+            }
+        }
+
         self.dst_names = vec![None; dst_namespaces.len()];
 
-        self.write.write_str("tiny\tv2\t0\t")?;
+        write!(self.write, "tiny\tv2\t{}\t", self.minor_version)?;
         self.write.write_str(src_namespace)?;
 
         for dst_namespace in dst_namespaces {
@@ -61,45 +799,80 @@ impl<W: std::fmt::Write> MappingVisitor for Tiny2Writer<W> {
             self.write.write_str(dst_namespace)?;
         }
 
+        self.writeln()?;
+
+        if self.escaped_names {
+            self.write_escaped_names_property()?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        // The property is written at most once, and enables escaping for the rest of the output.
+        if key == "escaped-names" {
+            if !self.escaped_names {
+                self.escaped_names = true;
+                self.write_escaped_names_property()?;
+            }
+
+            return Ok(());
+        }
+
+        self.start_line()?;
+
+        self.write_tab()?;
+        self.write.write_str(key)?;
+
+        if !value.is_empty() {
+            self.write_tab()?;
+            self.write.write_str(value)?;
+        }
+
         self.writeln()?;
         Ok(())
     }
 
     fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.start_line()?;
         self.write.write_str("c\t")?;
-        self.write.write_str(src_name)?;
+        self.write_escaped(src_name)?;
         Ok(true)
     }
 
     fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.start_line()?;
         self.write.write_str("\tf\t")?;
-        self.write.write_str(src_desc.ok_or(anyhow!("Tiny2Writer needs src desc!"))?)?;
+        self.write_escaped(src_desc.ok_or(anyhow!("Tiny2Writer needs src desc!"))?)?;
         self.write_tab()?;
-        self.write.write_str(src_name)?;
+        self.write_escaped(src_name)?;
         Ok(true)
     }
 
     fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.start_line()?;
         self.write.write_str("\tm\t")?;
-        self.write.write_str(src_desc.ok_or(anyhow!("Tiny2Writer needs src desc!"))?)?;
+        self.write_escaped(src_desc.ok_or(anyhow!("Tiny2Writer needs src desc!"))?)?;
         self.write_tab()?;
-        self.write.write_str(src_name)?;
+        self.write_escaped(src_name)?;
         Ok(true)
     }
 
     fn visit_method_arg(&mut self, _arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.start_line()?;
         self.write.write_str("\t\tp\t")?;
         write!(self.write, "{}", lv_index)?;
         self.write_tab()?;
 
         if let Some(src_name) = src_name {
-            self.write.write_str(src_name)?;
+            self.write_escaped(src_name)?;
         }
 
         Ok(true)
     }
 
     fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.start_line()?;
         self.write.write_str("\t\tv\t")?;
         write!(self.write, "{}", lv_index)?;
         self.write_tab()?;
@@ -109,7 +882,7 @@ impl<W: std::fmt::Write> MappingVisitor for Tiny2Writer<W> {
         self.write_tab()?;
 
         if let Some(src_name) = src_name {
-            self.write.write_str(src_name)?;
+            self.write_escaped(src_name)?;
         }
 
         Ok(true)
@@ -125,22 +898,24 @@ impl<W: std::fmt::Write> MappingVisitor for Tiny2Writer<W> {
             self.write_tab()?;
 
             if let Some(dst_name) = dst_name {
-                self.write.write_str(dst_name)?;
+                self.write_escaped(dst_name)?;
             }
         }
 
         self.dst_names.fill(None);
+        self.writeln()?;
         Ok(true)
     }
 
     fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        self.start_line()?;
+
         for _ in 0..target_kind.level() {
             self.write_tab()?;
         }
 
         self.write.write_str("\tc\t")?;
-        // TODO: espace newlines etc
-        self.write.write_str(comment)?;
+        self.write_escaped(comment)?;
         self.writeln()?;
         Ok(())
     }
@@ -14,14 +14,21 @@
  * limitations under the License.
 */
 
+/// Mapping visitors that transform mappings before passing them on to another visitor.
+pub mod adapter;
 /// JVM types and type descriptors.
 pub mod descriptor;
+/// Mapping visits as values that can be stored and replayed.
+pub mod events;
 /// Readers and writes for different mapping formats.
 pub mod format;
+/// In-memory mapping trees.
+pub mod tree;
 /// Definition of mapping visitors.
 pub mod visitor;
 
 /// The kind of a mappable element in a mapping file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MappedElementKind {
     /// A class (includes interfaces, annotations, enums etc.).
     /// Top-level and nested classes are not differentiated.
@@ -0,0 +1,202 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use anyhow::{anyhow, bail};
+use crate::MappedElementKind;
+use crate::visitor::MappingVisitor;
+use super::{ClassEntry, MappingTree, MethodEntry};
+
+/// Composes a chain of sequential mappings (`A -> B`, `B -> C`, `C -> D`) into a single mapping (`A -> D`).
+///
+/// Every mapping must have exactly one destination namespace, and it must match the source namespace
+/// of the next mapping. Elements are followed from the first mapping through each hop, and descriptors
+/// are remapped along the way to find the matching members in the next mapping.
+///
+/// The following rules apply to elements that drop out of the chain:
+/// - Only the elements of the first mapping are included in the result.
+///   Elements that only appear in later mappings are dropped.
+/// - An element that is missing from an intermediate mapping, or has no destination name there,
+///   keeps its name through that hop.
+/// - Method arguments are matched by their local variable index if known, and by their position otherwise.
+///   Local variables aren't included in the result.
+/// - The comment of an element is taken from the last mapping in which it has one.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::{compose_chain, MappingTree};
+///
+/// let read = |tiny: &str| -> anyhow::Result<MappingTree> {
+///     let mut tree = MappingTree::new();
+///     Tiny2Reader::read_str(tiny, &mut tree)?;
+///     Ok(tree)
+/// };
+///
+/// let first = read("tiny\t2\t0\tofficial\tintermediary\nc\ta\tclass_1\n\tf\tLa;\tb\tfield_1\n")?;
+/// let second = read("tiny\t2\t0\tintermediary\tnamed\nc\tclass_1\tcom/example/Foo\n\tf\tLclass_1;\tfield_1\tinstance\n")?;
+/// let third = read("tiny\t2\t0\tnamed\tcustom\nc\tcom/example/Foo\tcom/example/Bar\n")?;
+/// let composed = compose_chain(&[&first, &second, &third])?;
+///
+/// assert_eq!(composed.src_namespace(), Some("official"));
+/// assert_eq!(composed.dst_namespaces(), &["custom"]);
+/// let class = composed.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("com/example/Bar"));
+/// // The field isn't in the third mapping, so it keeps its name from the second one.
+/// assert_eq!(class.field("b", Some("La;")).unwrap().dst_name(0), Some("instance"));
+///
+/// assert!(compose_chain(&[&first, &third]).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn compose_chain(mappings: &[&MappingTree]) -> anyhow::Result<MappingTree> {
+    let first = mappings.first().ok_or_else(|| anyhow!("Cannot compose an empty chain of mappings"))?;
+
+    for (index, mapping) in mappings.iter().enumerate() {
+        if mapping.dst_namespaces().len() != 1 {
+            bail!("Mapping {} in the chain has {} destination namespaces, expected 1", index, mapping.dst_namespaces().len());
+        }
+    }
+
+    for (index, pair) in mappings.windows(2).enumerate() {
+        let dst_namespace = &pair[0].dst_namespaces()[0];
+
+        if pair[1].src_namespace() != Some(dst_namespace.as_str()) {
+            bail!(
+                "Mapping {} in the chain maps to {}, but mapping {} maps from {:?}",
+                index, dst_namespace, index + 1, pair[1].src_namespace()
+            );
+        }
+    }
+
+    let src_namespace = first.src_namespace().ok_or_else(|| anyhow!("Mapping 0 in the chain has no namespaces"))?;
+    let dst_namespace = &mappings[mappings.len() - 1].dst_namespaces()[0];
+
+    let mut result = MappingTree::new();
+    result.visit_namespaces(src_namespace, &[dst_namespace])?;
+
+    for class in first.classes() {
+        compose_class(mappings, class, &mut result)?;
+    }
+
+    result.visit_end()?;
+    Ok(result)
+}
+
+fn compose_class(mappings: &[&MappingTree], class: &ClassEntry, result: &mut MappingTree) -> anyhow::Result<()> {
+    // The entries of the class in each mapping, used for finding its members.
+    let mut entries = Vec::with_capacity(mappings.len());
+    let mut name = class.src_name();
+    let mut comment = None;
+
+    for mapping in mappings {
+        let entry = mapping.class(name);
+
+        if let Some(entry) = entry {
+            name = entry.dst_name(0).unwrap_or(name);
+            comment = entry.comment().or(comment);
+        }
+
+        entries.push(entry);
+    }
+
+    result.visit_class(class.src_name())?;
+    visit_element(result, MappedElementKind::Class, Some(name), comment)?;
+
+    for field in class.fields() {
+        let mut name = field.src_name();
+        let mut desc = field.src_desc().map(str::to_owned);
+        let mut comment = None;
+
+        for (mapping, owner) in mappings.iter().zip(&entries) {
+            if let Some(entry) = owner.and_then(|owner| owner.field(name, desc.as_deref())) {
+                name = entry.dst_name(0).unwrap_or(name);
+                comment = entry.comment().or(comment);
+            }
+
+            desc = desc.map(|desc| mapping.map_desc(&desc, 0));
+        }
+
+        result.visit_field(field.src_name(), field.src_desc())?;
+        visit_element(result, MappedElementKind::Field, Some(name), comment)?;
+    }
+
+    for method in class.methods() {
+        compose_method(mappings, &entries, method, result)?;
+    }
+
+    Ok(())
+}
+
+fn compose_method(
+    mappings: &[&MappingTree],
+    owners: &[Option<&ClassEntry>],
+    method: &MethodEntry,
+    result: &mut MappingTree,
+) -> anyhow::Result<()> {
+    // The entries of the method in each mapping, used for finding its arguments.
+    let mut entries = Vec::with_capacity(mappings.len());
+    let mut name = method.src_name();
+    let mut desc = method.src_desc().map(str::to_owned);
+    let mut comment = None;
+
+    for (mapping, owner) in mappings.iter().zip(owners) {
+        let entry = owner.and_then(|owner| owner.method(name, desc.as_deref()));
+
+        if let Some(entry) = entry {
+            name = entry.dst_name(0).unwrap_or(name);
+            comment = entry.comment().or(comment);
+        }
+
+        desc = desc.map(|desc| mapping.map_desc(&desc, 0));
+        entries.push(entry);
+    }
+
+    result.visit_method(method.src_name(), method.src_desc())?;
+    visit_element(result, MappedElementKind::Method, Some(name), comment)?;
+
+    for arg in method.args() {
+        let mut name = arg.src_name();
+        let mut comment = None;
+
+        for entry in entries.iter().flatten() {
+            if let Some(index) = entry.arg_index(arg.arg_position(), arg.lv_index()) {
+                let entry = &entry.args()[index];
+                name = entry.dst_name(0).or(name);
+                comment = entry.comment().or(comment);
+            }
+        }
+
+        result.visit_method_arg(arg.arg_position(), arg.lv_index(), arg.src_name())?;
+
+        visit_element(result, MappedElementKind::MethodArg, name, comment)?;
+    }
+
+    Ok(())
+}
+
+fn visit_element(result: &mut MappingTree, kind: MappedElementKind, name: Option<&str>, comment: Option<&str>) -> anyhow::Result<()> {
+    if let Some(name) = name {
+        result.visit_dst_name(kind, 0, name)?;
+    }
+
+    result.visit_element_content(kind)?;
+
+    if let Some(comment) = comment {
+        result.visit_comment(kind, comment)?;
+    }
+
+    Ok(())
+}
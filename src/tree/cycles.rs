@@ -0,0 +1,101 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use anyhow::{anyhow, bail};
+use super::MappingTree;
+
+/// A checker for circular class renames in a [`MappingTree`].
+///
+/// The class mappings of a single destination namespace form a graph from source names to destination names.
+/// A cycle in that graph, such as `A -> B` and `B -> A`, is usually the result of a bug
+/// in merging or composing mappings. Classes that map to themselves aren't considered cycles.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::srg::SrgReader;
+/// use jvm_obfuscation_mappings::tree::{CycleChecker, MappingTree};
+///
+/// let mut tree = MappingTree::new();
+/// SrgReader::read_str("CL: a b\nCL: b a\nCL: c c\nCL: d a\n", &mut tree)?;
+///
+/// assert_eq!(CycleChecker::find_cycles(&tree, "target")?, vec![vec!["a".to_owned(), "b".to_owned()]]);
+/// let error = CycleChecker::check(&tree, "target").unwrap_err();
+/// assert_eq!(error.to_string(), "Circular class renames in namespace target: a -> b -> a");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct CycleChecker;
+
+impl CycleChecker {
+    /// Finds the cycles of class renames in the destination namespace `namespace`.
+    ///
+    /// Each cycle is listed once, starting from the class that comes first in the tree.
+    pub fn find_cycles(tree: &MappingTree, namespace: &str) -> anyhow::Result<Vec<Vec<String>>> {
+        let namespace_index = tree.dst_namespace_index(namespace)
+            .ok_or_else(|| anyhow!("Unknown destination namespace {}", namespace))?;
+        let next = |name: &str| {
+            tree.class(name)
+                .and_then(|class| class.dst_name(namespace_index))
+                .filter(|&dst_name| dst_name != name)
+        };
+
+        let mut cycles = Vec::new();
+        let mut finished: HashSet<&str> = HashSet::new();
+
+        for class in tree.classes() {
+            // The classes on the current path, and their positions on it.
+            let mut path: Vec<&str> = Vec::new();
+            let mut positions: HashMap<&str, usize> = HashMap::new();
+            let mut current = Some(class.src_name());
+
+            while let Some(name) = current {
+                if finished.contains(name) {
+                    break;
+                }
+
+                if let Some(&start) = positions.get(name) {
+                    cycles.push(path[start..].iter().map(|&name| name.to_owned()).collect());
+                    break;
+                }
+
+                positions.insert(name, path.len());
+                path.push(name);
+                current = next(name);
+            }
+
+            finished.extend(path);
+        }
+
+        Ok(cycles)
+    }
+
+    /// Checks that there are no cycles of class renames in the destination namespace `namespace`.
+    ///
+    /// The error message lists the classes involved in each cycle.
+    pub fn check(tree: &MappingTree, namespace: &str) -> anyhow::Result<()> {
+        let cycles = CycleChecker::find_cycles(tree, namespace)?;
+
+        if !cycles.is_empty() {
+            let cycles: Vec<String> = cycles.iter()
+                .map(|cycle| format!("{} -> {}", cycle.join(" -> "), cycle[0]))
+                .collect();
+            bail!("Circular class renames in namespace {}: {}", namespace, cycles.join(", "));
+        }
+
+        Ok(())
+    }
+}
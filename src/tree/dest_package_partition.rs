@@ -0,0 +1,266 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{BTreeMap, HashSet};
+use anyhow::anyhow;
+use crate::MappedElementKind;
+use crate::events::MappingEvent;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+use super::MappingTree;
+
+/// A visitor that partitions mappings into trees by the destination package of each class.
+///
+/// Classes are routed by their destination name in the destination namespace chosen in [`new`](Self::new),
+/// falling back to the source name if they don't have one. The default package is keyed by an empty string.
+/// Since the destination name is only known after the class has been visited, the events of each class
+/// are buffered until its [`visit_element_content`](MappingVisitor::visit_element_content).
+///
+/// The header (namespaces and metadata) is replayed to every bucket. Each class must be visited only once,
+/// so this visitor requires [uniqueness](MappingFlag::NeedsUniqueness).
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::srg::SrgReader;
+/// use jvm_obfuscation_mappings::tree::DestPackagePartitionVisitor;
+///
+/// let mut partition = DestPackagePartitionVisitor::new("target");
+/// SrgReader::read_str("CL: a com/example/Foo\nCL: b net/example/Bar\nFD: b/c net/example/Bar/baz\n", &mut partition)?;
+///
+/// let buckets = partition.into_buckets();
+/// assert_eq!(buckets.keys().collect::<Vec<_>>(), ["com/example", "net/example"]);
+/// assert!(buckets["com/example"].class("a").is_some());
+/// assert!(buckets["com/example"].class("b").is_none());
+/// assert_eq!(buckets["net/example"].class("b").unwrap().fields()[0].dst_name(0), Some("baz"));
+/// assert_eq!(buckets["net/example"].dst_namespaces(), ["target"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct DestPackagePartitionVisitor {
+    namespace: String,
+    namespace_index: usize,
+    header: Vec<MappingEvent>,
+    buckets: BTreeMap<String, MappingTree>,
+    /// The buffered events of the current class, or `None` if the class has already been routed.
+    class_events: Option<Vec<MappingEvent>>,
+    class_src_name: String,
+    class_dst_name: Option<String>,
+    current_bucket: Option<String>,
+}
+
+impl DestPackagePartitionVisitor {
+    /// Creates a new partitioning visitor that routes classes by their names in the destination namespace `namespace`.
+    pub fn new(namespace: &str) -> DestPackagePartitionVisitor {
+        DestPackagePartitionVisitor {
+            namespace: namespace.to_owned(),
+            namespace_index: 0,
+            header: Vec::new(),
+            buckets: BTreeMap::new(),
+            class_events: None,
+            class_src_name: String::new(),
+            class_dst_name: None,
+            current_bucket: None,
+        }
+    }
+
+    /// Returns the buckets by their destination package.
+    pub fn buckets(&self) -> &BTreeMap<String, MappingTree> {
+        &self.buckets
+    }
+
+    /// Returns the buckets by their destination package, consuming the visitor.
+    pub fn into_buckets(self) -> BTreeMap<String, MappingTree> {
+        self.buckets
+    }
+
+    /// Returns the tree of the current bucket, if a class has been routed.
+    fn current_tree(&mut self) -> Option<&mut MappingTree> {
+        let bucket = self.current_bucket.as_ref()?;
+        self.buckets.get_mut(bucket)
+    }
+
+    /// Buffers an event of the current class if it hasn't been routed yet.
+    ///
+    /// Returns `true` if the event was buffered.
+    fn buffer(&mut self, event: MappingEvent) -> bool {
+        match &mut self.class_events {
+            Some(events) => {
+                events.push(event);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Routes the current class to its bucket and replays its buffered events.
+    fn route_class(&mut self) -> VisitResult<bool> {
+        let events = self.class_events.take().unwrap_or_default();
+        let name = self.class_dst_name.as_deref().unwrap_or(&self.class_src_name);
+        let package = name.rsplit_once('/').map_or("", |(package, _)| package).to_owned();
+
+        if !self.buckets.contains_key(&package) {
+            let mut tree = MappingTree::new();
+
+            for event in &self.header {
+                event.visit(&mut tree)?;
+            }
+
+            self.buckets.insert(package.clone(), tree);
+        }
+
+        let tree = self.buckets.get_mut(&package).unwrap();
+        self.current_bucket = Some(package);
+
+        for event in &events {
+            event.visit(tree)?;
+        }
+
+        tree.visit_element_content(MappedElementKind::Class)
+    }
+}
+
+impl MappingVisitor for DestPackagePartitionVisitor {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        HashSet::from([MappingFlag::NeedsUniqueness])
+    }
+
+    fn reset(&mut self) {
+        self.namespace_index = 0;
+        self.header.clear();
+        self.buckets.clear();
+        self.class_events = None;
+        self.class_dst_name = None;
+        self.current_bucket = None;
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        self.header.push(MappingEvent::Header);
+        Ok(true)
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.namespace_index = dst_namespaces.iter()
+            .position(|&namespace| namespace == self.namespace)
+            .ok_or_else(|| anyhow!("Unknown destination namespace {}", self.namespace))?;
+        self.header.push(MappingEvent::Namespaces {
+            src_namespace: src_namespace.to_owned(),
+            dst_namespaces: dst_namespaces.iter().map(|&namespace| namespace.to_owned()).collect(),
+        });
+        Ok(())
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        self.header.push(MappingEvent::Metadata { key: key.to_owned(), value: value.to_owned() });
+        Ok(())
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        self.header.push(MappingEvent::Content);
+        Ok(true)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_src_name = src_name.to_owned();
+        self.class_dst_name = None;
+        self.current_bucket = None;
+        self.class_events = Some(vec![MappingEvent::Class { src_name: src_name.to_owned() }]);
+        Ok(true)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        match self.current_tree() {
+            Some(tree) => tree.visit_field(src_name, src_desc),
+            None => Ok(false),
+        }
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        match self.current_tree() {
+            Some(tree) => tree.visit_method(src_name, src_desc),
+            None => Ok(false),
+        }
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        match self.current_tree() {
+            Some(tree) => tree.visit_method_arg(arg_position, lv_index, src_name),
+            None => Ok(false),
+        }
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        match self.current_tree() {
+            Some(tree) => tree.visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name),
+            None => Ok(false),
+        }
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        for tree in self.buckets.values_mut() {
+            tree.visit_end()?;
+        }
+
+        Ok(true)
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if target_kind == MappedElementKind::Class && namespace == self.namespace_index && self.class_events.is_some() {
+            self.class_dst_name = Some(name.to_owned());
+        }
+
+        if self.buffer(MappingEvent::DstName { target_kind, namespace, name: name.to_owned() }) {
+            return Ok(());
+        }
+
+        match self.current_tree() {
+            Some(tree) => tree.visit_dst_name(target_kind, namespace, name),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        if self.buffer(MappingEvent::DstDesc { target_kind, namespace, desc: desc.to_owned() }) {
+            return Ok(());
+        }
+
+        match self.current_tree() {
+            Some(tree) => tree.visit_dst_desc(target_kind, namespace, desc),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        if target_kind == MappedElementKind::Class && self.class_events.is_some() {
+            return self.route_class();
+        }
+
+        match self.current_tree() {
+            Some(tree) => tree.visit_element_content(target_kind),
+            None => Ok(false),
+        }
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        if self.buffer(MappingEvent::Comment { target_kind, comment: comment.to_owned() }) {
+            return Ok(());
+        }
+
+        match self.current_tree() {
+            Some(tree) => tree.visit_comment(target_kind, comment),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashMap;
+use super::MappingTree;
+
+type DstMemberKey = (usize, usize, String);
+type MemberMatches = Vec<(usize, Option<String>)>;
+
+/// Lookup tables from destination names to the indices of the elements in a [`MappingTree`].
+///
+/// Elements without a destination name in a namespace are indexed by their source names,
+/// and members are indexed along with their destination descriptors.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DstIndex {
+    /// Class indices keyed by namespace and destination name.
+    classes: HashMap<(usize, String), usize>,
+    /// Field indices and destination descriptors keyed by namespace, class index and destination name.
+    fields: HashMap<DstMemberKey, MemberMatches>,
+    /// Method indices and destination descriptors keyed by namespace, class index and destination name.
+    methods: HashMap<DstMemberKey, MemberMatches>,
+}
+
+impl DstIndex {
+    pub(crate) fn build(tree: &MappingTree) -> DstIndex {
+        let mut index = DstIndex::default();
+
+        for namespace in 0..tree.dst_namespaces().len() {
+            // Members share few distinct descriptors, so each one is only remapped once per namespace.
+            let mut descs: HashMap<&str, String> = HashMap::new();
+
+            for (class_index, class) in tree.classes().iter().enumerate() {
+                let class_name = class.dst_name(namespace).unwrap_or(class.src_name());
+                index.classes.entry((namespace, class_name.to_owned())).or_insert(class_index);
+
+                for (field_index, field) in class.fields().iter().enumerate() {
+                    let name = field.dst_name(namespace).unwrap_or(field.src_name());
+                    let desc = field.src_desc().map(|desc| map_desc(&mut descs, tree, desc, namespace));
+                    index.fields.entry((namespace, class_index, name.to_owned())).or_default().push((field_index, desc));
+                }
+
+                for (method_index, method) in class.methods().iter().enumerate() {
+                    let name = method.dst_name(namespace).unwrap_or(method.src_name());
+                    let desc = method.src_desc().map(|desc| map_desc(&mut descs, tree, desc, namespace));
+                    index.methods.entry((namespace, class_index, name.to_owned())).or_default().push((method_index, desc));
+                }
+            }
+        }
+
+        index
+    }
+
+    pub(crate) fn class(&self, namespace: usize, dst_name: &str) -> Option<usize> {
+        self.classes.get(&(namespace, dst_name.to_owned())).copied()
+    }
+
+    pub(crate) fn field(&self, namespace: usize, class_index: usize, dst_name: &str, dst_desc: Option<&str>) -> Option<usize> {
+        find_member(&self.fields, namespace, class_index, dst_name, dst_desc)
+    }
+
+    pub(crate) fn method(&self, namespace: usize, class_index: usize, dst_name: &str, dst_desc: Option<&str>) -> Option<usize> {
+        find_member(&self.methods, namespace, class_index, dst_name, dst_desc)
+    }
+}
+
+/// Remaps a source descriptor to the namespace `namespace`, reusing earlier results from `descs`.
+fn map_desc<'a>(descs: &mut HashMap<&'a str, String>, tree: &MappingTree, desc: &'a str, namespace: usize) -> String {
+    descs.entry(desc).or_insert_with(|| tree.map_desc(desc, namespace)).clone()
+}
+
+/// Finds the first member with a matching name, and a matching descriptor if one is given.
+fn find_member(
+    members: &HashMap<DstMemberKey, MemberMatches>,
+    namespace: usize,
+    class_index: usize,
+    dst_name: &str,
+    dst_desc: Option<&str>,
+) -> Option<usize> {
+    members.get(&(namespace, class_index, dst_name.to_owned()))?
+        .iter()
+        .find(|(_, desc)| dst_desc.is_none() || desc.as_deref() == dst_desc)
+        .map(|&(index, _)| index)
+}
@@ -0,0 +1,392 @@
+/*
+ * Copyright (c) 2021-2022 FabricMC, 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::sync::Arc;
+use super::intern::DescInterner;
+
+/// A class stored in a [`MappingTree`](super::MappingTree).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassEntry {
+    pub(crate) src_name: String,
+    pub(crate) dst_names: Vec<Option<String>>,
+    pub(crate) comment: Option<String>,
+    pub(crate) fields: Vec<FieldEntry>,
+    pub(crate) methods: Vec<MethodEntry>,
+}
+
+/// A field stored in a [`MappingTree`](super::MappingTree).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldEntry {
+    pub(crate) src_name: String,
+    pub(crate) src_desc: Option<Arc<str>>,
+    pub(crate) dst_names: Vec<Option<String>>,
+    pub(crate) comment: Option<String>,
+}
+
+/// A method stored in a [`MappingTree`](super::MappingTree).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodEntry {
+    pub(crate) src_name: String,
+    pub(crate) src_desc: Option<Arc<str>>,
+    pub(crate) dst_names: Vec<Option<String>>,
+    pub(crate) comment: Option<String>,
+    pub(crate) args: Vec<MethodArgEntry>,
+    pub(crate) vars: Vec<MethodVarEntry>,
+}
+
+/// A method argument stored in a [`MappingTree`](super::MappingTree).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodArgEntry {
+    pub(crate) arg_position: i32,
+    pub(crate) lv_index: i32,
+    pub(crate) src_name: Option<String>,
+    pub(crate) dst_names: Vec<Option<String>>,
+    pub(crate) comment: Option<String>,
+}
+
+/// A method local variable stored in a [`MappingTree`](super::MappingTree).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodVarEntry {
+    pub(crate) lvt_row_index: i32,
+    pub(crate) lv_index: i32,
+    pub(crate) start_op_idx: i32,
+    pub(crate) src_name: Option<String>,
+    pub(crate) dst_names: Vec<Option<String>>,
+    pub(crate) comment: Option<String>,
+}
+
+/// Returns the destination name at the namespace index `namespace`, if present.
+fn get_dst_name(dst_names: &[Option<String>], namespace: usize) -> Option<&str> {
+    dst_names.get(namespace).and_then(|name| name.as_deref())
+}
+
+/// Sets the destination name at the namespace index `namespace`, growing the list if needed.
+pub(crate) fn set_dst_name(dst_names: &mut Vec<Option<String>>, namespace: usize, name: Option<String>) {
+    if dst_names.len() <= namespace {
+        dst_names.resize(namespace + 1, None);
+    }
+
+    dst_names[namespace] = name;
+}
+
+impl ClassEntry {
+    pub(crate) fn new(src_name: &str) -> ClassEntry {
+        ClassEntry {
+            src_name: src_name.to_owned(),
+            dst_names: Vec::new(),
+            comment: None,
+            fields: Vec::new(),
+            methods: Vec::new(),
+        }
+    }
+
+    /// Returns the source name of this class.
+    pub fn src_name(&self) -> &str {
+        &self.src_name
+    }
+
+    /// Returns the destination name of this class in the destination namespace `namespace`.
+    pub fn dst_name(&self, namespace: usize) -> Option<&str> {
+        get_dst_name(&self.dst_names, namespace)
+    }
+
+    /// Returns the comment of this class.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Returns the fields of this class in insertion order.
+    pub fn fields(&self) -> &[FieldEntry] {
+        &self.fields
+    }
+
+    /// Returns the methods of this class in insertion order.
+    pub fn methods(&self) -> &[MethodEntry] {
+        &self.methods
+    }
+
+    /// Finds a field by its source name and descriptor.
+    ///
+    /// If either `src_desc` or the stored descriptor is missing, only the names are compared.
+    pub fn field(&self, src_name: &str, src_desc: Option<&str>) -> Option<&FieldEntry> {
+        self.field_index(src_name, src_desc).map(|index| &self.fields[index])
+    }
+
+    /// Finds a method by its source name and descriptor.
+    ///
+    /// If either `src_desc` or the stored descriptor is missing, only the names are compared.
+    pub fn method(&self, src_name: &str, src_desc: Option<&str>) -> Option<&MethodEntry> {
+        self.method_index(src_name, src_desc).map(|index| &self.methods[index])
+    }
+
+    pub(crate) fn field_index(&self, src_name: &str, src_desc: Option<&str>) -> Option<usize> {
+        self.fields.iter().position(|field| member_matches(&field.src_name, field.src_desc.as_deref(), src_name, src_desc))
+    }
+
+    pub(crate) fn method_index(&self, src_name: &str, src_desc: Option<&str>) -> Option<usize> {
+        self.methods.iter().position(|method| member_matches(&method.src_name, method.src_desc.as_deref(), src_name, src_desc))
+    }
+
+    /// Finds or inserts a field, filling in a missing descriptor if one is provided.
+    pub(crate) fn field_or_insert(&mut self, src_name: &str, src_desc: Option<&str>, interner: &mut DescInterner) -> usize {
+        match self.field_index(src_name, src_desc) {
+            Some(index) => {
+                let field = &mut self.fields[index];
+
+                if field.src_desc.is_none() {
+                    field.src_desc = src_desc.map(|desc| interner.intern(desc));
+                }
+
+                index
+            }
+            None => {
+                self.fields.push(FieldEntry {
+                    src_name: src_name.to_owned(),
+                    src_desc: src_desc.map(|desc| interner.intern(desc)),
+                    dst_names: Vec::new(),
+                    comment: None,
+                });
+                self.fields.len() - 1
+            }
+        }
+    }
+
+    /// Finds or inserts a method, filling in a missing descriptor if one is provided.
+    pub(crate) fn method_or_insert(&mut self, src_name: &str, src_desc: Option<&str>, interner: &mut DescInterner) -> usize {
+        match self.method_index(src_name, src_desc) {
+            Some(index) => {
+                let method = &mut self.methods[index];
+
+                if method.src_desc.is_none() {
+                    method.src_desc = src_desc.map(|desc| interner.intern(desc));
+                }
+
+                index
+            }
+            None => {
+                self.methods.push(MethodEntry {
+                    src_name: src_name.to_owned(),
+                    src_desc: src_desc.map(|desc| interner.intern(desc)),
+                    dst_names: Vec::new(),
+                    comment: None,
+                    args: Vec::new(),
+                    vars: Vec::new(),
+                });
+                self.methods.len() - 1
+            }
+        }
+    }
+}
+
+fn member_matches(name: &str, desc: Option<&str>, query_name: &str, query_desc: Option<&str>) -> bool {
+    name == query_name && match (desc, query_desc) {
+        (Some(desc), Some(query_desc)) => desc == query_desc,
+        _ => true,
+    }
+}
+
+impl FieldEntry {
+    /// Returns the source name of this field.
+    pub fn src_name(&self) -> &str {
+        &self.src_name
+    }
+
+    /// Returns the source descriptor of this field.
+    pub fn src_desc(&self) -> Option<&str> {
+        self.src_desc.as_deref()
+    }
+
+    /// Returns the destination name of this field in the destination namespace `namespace`.
+    pub fn dst_name(&self, namespace: usize) -> Option<&str> {
+        get_dst_name(&self.dst_names, namespace)
+    }
+
+    /// Returns the comment of this field.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+}
+
+impl MethodEntry {
+    /// Returns the source name of this method.
+    pub fn src_name(&self) -> &str {
+        &self.src_name
+    }
+
+    /// Returns the source descriptor of this method.
+    pub fn src_desc(&self) -> Option<&str> {
+        self.src_desc.as_deref()
+    }
+
+    /// Returns the destination name of this method in the destination namespace `namespace`.
+    pub fn dst_name(&self, namespace: usize) -> Option<&str> {
+        get_dst_name(&self.dst_names, namespace)
+    }
+
+    /// Returns the comment of this method.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Returns the arguments of this method in insertion order.
+    pub fn args(&self) -> &[MethodArgEntry] {
+        &self.args
+    }
+
+    /// Returns the local variables of this method in insertion order.
+    pub fn vars(&self) -> &[MethodVarEntry] {
+        &self.vars
+    }
+
+    /// Finds an argument by its local variable index if known, and by its position otherwise.
+    pub(crate) fn arg_index(&self, arg_position: i32, lv_index: i32) -> Option<usize> {
+        self.args.iter().position(|arg| {
+            if lv_index >= 0 && arg.lv_index >= 0 {
+                arg.lv_index == lv_index
+            } else {
+                arg_position >= 0 && arg.arg_position == arg_position
+            }
+        })
+    }
+
+    /// Finds or inserts an argument.
+    ///
+    /// Arguments are matched by their local variable index if known, and by their position otherwise.
+    pub(crate) fn arg_or_insert(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> usize {
+        match self.arg_index(arg_position, lv_index) {
+            Some(index) => {
+                let arg = &mut self.args[index];
+
+                if arg.arg_position < 0 {
+                    arg.arg_position = arg_position;
+                }
+
+                if arg.lv_index < 0 {
+                    arg.lv_index = lv_index;
+                }
+
+                if arg.src_name.is_none() {
+                    arg.src_name = src_name.map(str::to_owned);
+                }
+
+                index
+            }
+            None => {
+                self.args.push(MethodArgEntry {
+                    arg_position,
+                    lv_index,
+                    src_name: src_name.map(str::to_owned),
+                    dst_names: Vec::new(),
+                    comment: None,
+                });
+                self.args.len() - 1
+            }
+        }
+    }
+
+    /// Finds or inserts a local variable.
+    ///
+    /// Variables are matched by their local variable index and start opcode index.
+    pub(crate) fn var_or_insert(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> usize {
+        let existing = self.vars.iter().position(|var| var.lv_index == lv_index && var.start_op_idx == start_op_idx);
+
+        match existing {
+            Some(index) => {
+                let var = &mut self.vars[index];
+
+                if var.lvt_row_index < 0 {
+                    var.lvt_row_index = lvt_row_index;
+                }
+
+                if var.src_name.is_none() {
+                    var.src_name = src_name.map(str::to_owned);
+                }
+
+                index
+            }
+            None => {
+                self.vars.push(MethodVarEntry {
+                    lvt_row_index,
+                    lv_index,
+                    start_op_idx,
+                    src_name: src_name.map(str::to_owned),
+                    dst_names: Vec::new(),
+                    comment: None,
+                });
+                self.vars.len() - 1
+            }
+        }
+    }
+}
+
+impl MethodArgEntry {
+    /// Returns the position of this argument in the parameter list, or -1 if unknown.
+    pub fn arg_position(&self) -> i32 {
+        self.arg_position
+    }
+
+    /// Returns the local variable index of this argument, or -1 if unknown.
+    pub fn lv_index(&self) -> i32 {
+        self.lv_index
+    }
+
+    /// Returns the source name of this argument.
+    pub fn src_name(&self) -> Option<&str> {
+        self.src_name.as_deref()
+    }
+
+    /// Returns the destination name of this argument in the destination namespace `namespace`.
+    pub fn dst_name(&self, namespace: usize) -> Option<&str> {
+        get_dst_name(&self.dst_names, namespace)
+    }
+
+    /// Returns the comment of this argument.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+}
+
+impl MethodVarEntry {
+    /// Returns the row index of this variable in the local variable table, or -1 if unknown.
+    pub fn lvt_row_index(&self) -> i32 {
+        self.lvt_row_index
+    }
+
+    /// Returns the local variable index of this variable.
+    pub fn lv_index(&self) -> i32 {
+        self.lv_index
+    }
+
+    /// Returns the index of the first opcode where this variable is in scope.
+    pub fn start_op_idx(&self) -> i32 {
+        self.start_op_idx
+    }
+
+    /// Returns the source name of this variable.
+    pub fn src_name(&self) -> Option<&str> {
+        self.src_name.as_deref()
+    }
+
+    /// Returns the destination name of this variable in the destination namespace `namespace`.
+    pub fn dst_name(&self, namespace: usize) -> Option<&str> {
+        get_dst_name(&self.dst_names, namespace)
+    }
+
+    /// Returns the comment of this variable.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+}
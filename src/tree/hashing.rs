@@ -0,0 +1,228 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// A visitor that computes a content hash for each class.
+///
+/// The hash of a class covers its source name, destination names and comment, as well as
+/// the same data of its fields, methods, method arguments and method variables.
+/// Destination descriptors aren't included, since not all producers supply them.
+///
+/// Members are hashed independently and combined in sorted order, so the order in which they are visited
+/// doesn't affect the hash of their class. The hash function is implemented by this crate,
+/// which keeps the hashes stable across runs and compiler versions.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::srg::SrgReader;
+/// use jvm_obfuscation_mappings::tree::HashingVisitor;
+///
+/// let hash = |srg: &str| -> anyhow::Result<u64> {
+///     let mut visitor = HashingVisitor::new();
+///     SrgReader::read_str(srg, &mut visitor)?;
+///     Ok(visitor.hashes()["a"])
+/// };
+///
+/// let original = hash("CL: a Foo\nFD: a/b Foo/first\nFD: a/c Foo/second\n")?;
+/// let reordered = hash("CL: a Foo\nFD: a/c Foo/second\nFD: a/b Foo/first\n")?;
+/// let renamed = hash("CL: a Foo\nFD: a/b Foo/first\nFD: a/c Foo/renamed\n")?;
+/// assert_eq!(original, reordered);
+/// assert_ne!(original, renamed);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HashingVisitor {
+    hashes: HashMap<String, u64>,
+    class_src_name: String,
+    /// The hashers of the elements that are currently being visited, indexed by their nesting level.
+    frames: Vec<Frame>,
+}
+
+#[derive(Debug, Clone)]
+struct Frame {
+    hasher: Fnv1aHasher,
+    children: Vec<u64>,
+}
+
+impl HashingVisitor {
+    /// Creates a new hashing visitor.
+    pub fn new() -> HashingVisitor {
+        HashingVisitor::default()
+    }
+
+    /// Returns the hashes of the visited classes by their source names.
+    pub fn hashes(&self) -> &HashMap<String, u64> {
+        &self.hashes
+    }
+
+    /// Returns the hashes of the visited classes by their source names, consuming the visitor.
+    pub fn into_hashes(self) -> HashMap<String, u64> {
+        self.hashes
+    }
+
+    /// Finishes all elements at `level` or deeper and starts a new element at `level`.
+    fn start_element(&mut self, level: u32, tag: u8, parts: &[Option<&str>], numbers: &[i32]) {
+        self.finish_elements(level);
+
+        let mut hasher = Fnv1aHasher::new();
+        hasher.write(&[tag]);
+
+        for number in numbers {
+            hasher.write(&number.to_le_bytes());
+        }
+
+        for part in parts {
+            hasher.write_optional_str(*part);
+        }
+
+        self.frames.push(Frame { hasher, children: Vec::new() });
+    }
+
+    /// Finishes all elements at `level` or deeper, adding their hashes to their parents.
+    fn finish_elements(&mut self, level: u32) {
+        while self.frames.len() > level as usize {
+            let mut frame = self.frames.pop().unwrap();
+            frame.children.sort_unstable();
+
+            for child in frame.children {
+                frame.hasher.write(&child.to_le_bytes());
+            }
+
+            let hash = frame.hasher.finish();
+
+            match self.frames.last_mut() {
+                Some(parent) => parent.children.push(hash),
+                None => {
+                    self.hashes.insert(std::mem::take(&mut self.class_src_name), hash);
+                }
+            }
+        }
+    }
+
+    fn current_hasher(&mut self, target_kind: MappedElementKind) -> Option<&mut Fnv1aHasher> {
+        self.frames.get_mut(target_kind.level() as usize).map(|frame| &mut frame.hasher)
+    }
+}
+
+impl MappingVisitor for HashingVisitor {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        HashSet::from([MappingFlag::NeedsUniqueness])
+    }
+
+    fn reset(&mut self) {
+        self.frames.clear();
+    }
+
+    fn visit_namespaces(&mut self, _src_namespace: &str, _dst_namespaces: &[&str]) -> VisitResult<()> {
+        Ok(())
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.start_element(0, b'c', &[Some(src_name)], &[]);
+        self.class_src_name = src_name.to_owned();
+        Ok(true)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.start_element(1, b'f', &[Some(src_name), src_desc], &[]);
+        Ok(true)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.start_element(1, b'm', &[Some(src_name), src_desc], &[]);
+        Ok(true)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.start_element(2, b'p', &[src_name], &[arg_position, lv_index]);
+        Ok(true)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        self.start_element(2, b'v', &[src_name], &[lvt_row_index, lv_index, start_op_idx]);
+        Ok(true)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.finish_elements(0);
+        Ok(true)
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if let Some(hasher) = self.current_hasher(target_kind) {
+            hasher.write(b"d");
+            hasher.write(&(namespace as u64).to_le_bytes());
+            hasher.write_optional_str(Some(name));
+        }
+
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, _target_kind: MappedElementKind) -> VisitResult<bool> {
+        Ok(true)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        if let Some(hasher) = self.current_hasher(target_kind) {
+            hasher.write(b"#");
+            hasher.write_optional_str(Some(comment));
+        }
+
+        Ok(())
+    }
+}
+
+/// A 64-bit FNV-1a hasher, used instead of the standard library hashers
+/// because their output isn't guaranteed to be stable.
+#[derive(Debug, Clone)]
+struct Fnv1aHasher {
+    state: u64,
+}
+
+impl Fnv1aHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    fn new() -> Fnv1aHasher {
+        Fnv1aHasher { state: Fnv1aHasher::OFFSET_BASIS }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state ^= byte as u64;
+            self.state = self.state.wrapping_mul(Fnv1aHasher::PRIME);
+        }
+    }
+
+    /// Writes a length-prefixed string, or a marker for a missing one.
+    fn write_optional_str(&mut self, s: Option<&str>) {
+        match s {
+            Some(s) => {
+                self.write(&(s.len() as u64).to_le_bytes());
+                self.write(s.as_bytes());
+            }
+            None => self.write(&u64::MAX.to_le_bytes()),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
@@ -0,0 +1,47 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Creates the shared strings used for member descriptors in a [`MappingTree`](super::MappingTree).
+///
+/// When interning is disabled, every call allocates a new string.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DescInterner {
+    strings: Option<HashSet<Arc<str>>>,
+}
+
+impl DescInterner {
+    /// Creates an interner that shares equal strings.
+    pub(crate) fn enabled() -> DescInterner {
+        DescInterner { strings: Some(HashSet::new()) }
+    }
+
+    pub(crate) fn intern(&mut self, s: &str) -> Arc<str> {
+        match &mut self.strings {
+            Some(strings) => match strings.get(s) {
+                Some(interned) => interned.clone(),
+                None => {
+                    let interned: Arc<str> = Arc::from(s);
+                    strings.insert(interned.clone());
+                    interned
+                }
+            },
+            None => Arc::from(s),
+        }
+    }
+}
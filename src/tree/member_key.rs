@@ -0,0 +1,59 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use crate::MappedElementKind;
+use crate::descriptor::ClassName;
+
+/// The source identity of a field or method, usable as a key in hash-based collections.
+///
+/// Two keys are equal if all of their parts are equal, so members that only differ
+/// in their descriptors have distinct keys. Keys for the members of a tree can be
+/// created with [`MappingTree::member_keys`](super::MappingTree::member_keys).
+///
+/// The tree doesn't store its members by these keys: its lookups such as
+/// [`ClassEntry::field`](super::ClassEntry::field) treat a missing descriptor as matching any descriptor,
+/// which a key compared by equality can't express. A key without a descriptor only equals other keys without one.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::descriptor::ClassName;
+/// use jvm_obfuscation_mappings::tree::MemberKey;
+///
+/// let key = |desc: &str| MemberKey {
+///     owner: ClassName::from_internal_name("a"),
+///     name: "b".to_owned(),
+///     desc: Some(desc.to_owned()),
+///     kind: MappedElementKind::Field,
+/// };
+///
+/// let keys = HashSet::from([key("I"), key("I"), key("J")]);
+/// assert_eq!(keys.len(), 2);
+/// assert!(keys.contains(&key("I")));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MemberKey {
+    /// The source name of the class that owns the member.
+    pub owner: ClassName,
+    /// The source name of the member.
+    pub name: String,
+    /// The source descriptor of the member.
+    pub desc: Option<String>,
+    /// The kind of the member, either [`MappedElementKind::Field`] or [`MappedElementKind::Method`].
+    pub kind: MappedElementKind,
+}
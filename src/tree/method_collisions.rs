@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::HashMap;
+use anyhow::{anyhow, bail};
+use crate::descriptor::remap_descriptor;
+use super::MappingTree;
+
+/// Two methods of the same class that have the same name and descriptor in a destination namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodCollision {
+    /// The source name of the class containing the methods.
+    pub owner: String,
+    /// The source name and descriptor of the method that comes first in the class.
+    pub first: (String, String),
+    /// The source name and descriptor of the method that comes second in the class.
+    pub second: (String, String),
+    /// The shared destination name and descriptor.
+    pub dst: (String, String),
+}
+
+/// A checker for methods that collapse to the same name and descriptor in a destination namespace.
+///
+/// Two methods of a class with the same destination name and destination descriptor are illegal,
+/// and usually the result of a mistake such as mapping two classes to the same name.
+/// The destination descriptors are computed by remapping the source descriptors with the class mappings
+/// of the tree, so this check needs a complete [`MappingTree`]. Methods without a source descriptor are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::srg::SrgReader;
+/// use jvm_obfuscation_mappings::tree::{MappingTree, MethodCollisionChecker};
+///
+/// // Both a and b are mapped to Foo, so the overloads of m collide.
+/// let srg = "CL: a Foo\n\
+///            CL: b Foo\n\
+///            MD: c/m (La;)V c/run (LFoo;)V\n\
+///            MD: c/m (Lb;)V c/run (LFoo;)V\n";
+/// let mut tree = MappingTree::new();
+/// SrgReader::read_str(srg, &mut tree)?;
+///
+/// let collisions = MethodCollisionChecker::find_collisions(&tree, "target")?;
+/// assert_eq!(collisions.len(), 1);
+/// assert_eq!(collisions[0].dst, ("run".to_owned(), "(LFoo;)V".to_owned()));
+///
+/// let error = MethodCollisionChecker::check(&tree, "target").unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "Methods m(La;)V and m(Lb;)V in class c both map to run(LFoo;)V in namespace target"
+/// );
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct MethodCollisionChecker;
+
+impl MethodCollisionChecker {
+    /// Finds the colliding methods in the destination namespace `namespace`.
+    ///
+    /// If more than two methods collide, each one after the first is reported together with the first.
+    pub fn find_collisions(tree: &MappingTree, namespace: &str) -> anyhow::Result<Vec<MethodCollision>> {
+        let namespace_index = tree.dst_namespace_index(namespace)
+            .ok_or_else(|| anyhow!("Unknown destination namespace {}", namespace))?;
+        let mut collisions = Vec::new();
+
+        for class in tree.classes() {
+            let mut seen: HashMap<(&str, String), (&str, &str)> = HashMap::new();
+
+            for method in class.methods() {
+                let src_desc = match method.src_desc() {
+                    Some(src_desc) => src_desc,
+                    None => continue,
+                };
+                let dst_name = method.dst_name(namespace_index).unwrap_or(method.src_name());
+                let dst_desc = remap_descriptor(src_desc, |name| {
+                    tree.class(name).and_then(|class| class.dst_name(namespace_index)).map(str::to_owned)
+                });
+
+                match seen.get(&(dst_name, dst_desc.clone())) {
+                    Some(&(first_name, first_desc)) => collisions.push(MethodCollision {
+                        owner: class.src_name().to_owned(),
+                        first: (first_name.to_owned(), first_desc.to_owned()),
+                        second: (method.src_name().to_owned(), src_desc.to_owned()),
+                        dst: (dst_name.to_owned(), dst_desc),
+                    }),
+                    None => {
+                        seen.insert((dst_name, dst_desc), (method.src_name(), src_desc));
+                    }
+                }
+            }
+        }
+
+        Ok(collisions)
+    }
+
+    /// Checks that no methods collide in the destination namespace `namespace`.
+    ///
+    /// The error message names the source methods of each collision.
+    pub fn check(tree: &MappingTree, namespace: &str) -> anyhow::Result<()> {
+        let collisions = MethodCollisionChecker::find_collisions(tree, namespace)?;
+
+        if !collisions.is_empty() {
+            let collisions: Vec<String> = collisions.iter()
+                .map(|collision| format!(
+                    "Methods {}{} and {}{} in class {} both map to {}{}",
+                    collision.first.0, collision.first.1, collision.second.0, collision.second.1,
+                    collision.owner, collision.dst.0, collision.dst.1
+                ))
+                .collect();
+            bail!("{} in namespace {}", collisions.join(", "), namespace);
+        }
+
+        Ok(())
+    }
+}
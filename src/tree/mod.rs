@@ -0,0 +1,1144 @@
+/*
+ * Copyright (c) 2021-2022 FabricMC, 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+mod compose;
+mod cycles;
+mod dest_package_partition;
+mod dst_index;
+mod entry;
+mod hashing;
+mod intern;
+mod member_key;
+mod method_collisions;
+mod referential_integrity;
+mod reverse_index;
+mod single_namespace;
+
+pub use compose::*;
+pub use cycles::*;
+pub use dest_package_partition::*;
+pub use entry::*;
+pub use hashing::*;
+pub use member_key::*;
+pub use method_collisions::*;
+pub use referential_integrity::*;
+pub use reverse_index::*;
+
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use std::sync::OnceLock;
+use anyhow::{anyhow, bail, Context};
+use crate::MappedElementKind;
+use crate::descriptor::{remap_descriptor, ClassName, MethodDescriptor};
+use crate::events::{EventVisitor, MappingEvent};
+use crate::format::MappingFormat;
+use crate::format::enigma::{EnigmaSingleFileReader, EnigmaSingleFileWriter};
+use crate::format::proguard::ProGuardReader;
+use crate::format::srg::{SrgReader, SrgWriter};
+use crate::format::tiny2::{Tiny2Reader, Tiny2Writer};
+use dst_index::DstIndex;
+use intern::DescInterner;
+use single_namespace::SingleNamespaceVisitor;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// An in-memory mapping tree.
+///
+/// A mapping tree is itself a [`MappingVisitor`], so it can be filled by any reader.
+/// Its contents can then be queried directly or replayed into another visitor using [`accept`][Self::accept].
+///
+/// Visiting a tree that already contains mappings merges the new data into it:
+/// the source namespaces have to match, destination namespaces are matched by name
+/// and unknown destination namespaces are appended. Elements are matched by their source identity,
+/// so a class visited again (for example in a later pass) gets the new members added to its existing entry.
+/// Destination names and comments that are already set aren't replaced, unless they're empty.
+///
+/// Trees are [`Send`] and [`Sync`]: the only interior mutability is the thread-safe cache behind the
+/// destination name queries such as [`class_by_dst`][Self::class_by_dst]. A filled tree can be
+/// wrapped in an [`Arc`](std::sync::Arc) and queried from many threads at once without cloning it.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut tree = MappingTree::new();
+/// tree.visit_namespaces("official", &["named"])?;
+/// tree.visit_class("a")?;
+/// tree.visit_dst_name(MappedElementKind::Class, 0, "com/example/Example")?;
+/// tree.visit_element_content(MappedElementKind::Class)?;
+/// tree.visit_end()?;
+///
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("com/example/Example"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Merging a class whose members are split across two passes:
+///
+/// ```
+/// use jvm_obfuscation_mappings::MappedElementKind;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let mut tree = MappingTree::new();
+///
+/// for (class_name, comment, field) in [("com/example/Foo", "First", "b"), ("com/example/Other", "Second", "c")] {
+///     tree.visit_namespaces("official", &["named"])?;
+///     tree.visit_class("a")?;
+///     tree.visit_dst_name(MappedElementKind::Class, 0, class_name)?;
+///     tree.visit_element_content(MappedElementKind::Class)?;
+///     tree.visit_comment(MappedElementKind::Class, comment)?;
+///     tree.visit_field(field, Some("I"))?;
+///     tree.visit_element_content(MappedElementKind::Field)?;
+///     tree.visit_end()?;
+/// }
+///
+/// assert_eq!(tree.class_count(), 1);
+/// let class = tree.class("a").unwrap();
+/// assert_eq!(class.dst_name(0), Some("com/example/Foo"));
+/// assert_eq!(class.comment(), Some("First"));
+/// assert_eq!(class.fields().len(), 2);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MappingTree {
+    src_namespace: Option<String>,
+    dst_namespaces: Vec<String>,
+    metadata: Vec<(String, String)>,
+    classes: Vec<ClassEntry>,
+    class_indices: HashMap<String, usize>,
+    desc_interner: DescInterner,
+    /// The lookup tables for destination name queries, built on first use.
+    dst_index: OnceLock<DstIndex>,
+    // Visitation state
+    dst_namespace_map: Vec<usize>,
+    current_class: Option<usize>,
+    current_field: Option<usize>,
+    current_method: Option<usize>,
+    current_arg: Option<usize>,
+    current_var: Option<usize>,
+}
+
+impl MappingTree {
+    /// Creates a new, empty mapping tree.
+    pub fn new() -> MappingTree {
+        MappingTree::default()
+    }
+
+    /// Creates a new, empty mapping tree that shares the storage of equal member descriptors.
+    pub(crate) fn with_interned_descs() -> MappingTree {
+        MappingTree { desc_interner: DescInterner::enabled(), ..MappingTree::default() }
+    }
+
+    /// Returns the source namespace of this tree, or `None` if no namespaces have been visited yet.
+    pub fn src_namespace(&self) -> Option<&str> {
+        self.src_namespace.as_deref()
+    }
+
+    /// Returns the destination namespaces of this tree.
+    pub fn dst_namespaces(&self) -> &[String] {
+        &self.dst_namespaces
+    }
+
+    /// Returns the index of the destination namespace called `namespace`.
+    pub fn dst_namespace_index(&self, namespace: &str) -> Option<usize> {
+        self.dst_namespaces.iter().position(|ns| ns == namespace)
+    }
+
+    /// Returns the metadata properties of this tree in insertion order.
+    pub fn metadata(&self) -> &[(String, String)] {
+        &self.metadata
+    }
+
+    /// Returns the classes of this tree in insertion order.
+    pub fn classes(&self) -> &[ClassEntry] {
+        &self.classes
+    }
+
+    /// Takes the classes out of this tree.
+    pub(crate) fn into_classes(self) -> Vec<ClassEntry> {
+        self.classes
+    }
+
+    /// Finds a class by its source name.
+    pub fn class(&self, src_name: &str) -> Option<&ClassEntry> {
+        self.class_indices.get(src_name).map(|&index| &self.classes[index])
+    }
+
+    /// Finds a field by its owner's source name and its source name, ignoring descriptors.
+    ///
+    /// This is useful for querying fields that may have been read with or without descriptors.
+    /// Returns `Ok(None)` if there's no such field, and an error if the class has
+    /// multiple fields with the name but different descriptors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+    ///             c\ta\tcom/example/Foo\n\
+    ///             \tf\tI\tb\tcount\n\
+    ///             \tf\tZ\tc\tenabled\n\
+    ///             \tf\tJ\tc\ttimestamp\n";
+    /// let mut tree = MappingTree::new();
+    /// Tiny2Reader::read_str(tiny, &mut tree)?;
+    ///
+    /// assert_eq!(tree.get_field_any_desc("a", "b")?.unwrap().dst_name(0), Some("count"));
+    /// assert!(tree.get_field_any_desc("a", "d")?.is_none());
+    /// assert!(tree.get_field_any_desc("a", "c").is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn get_field_any_desc(&self, owner: &str, src_name: &str) -> anyhow::Result<Option<&FieldEntry>> {
+        let class = match self.class(owner) {
+            Some(class) => class,
+            None => return Ok(None),
+        };
+
+        let mut matching = class.fields().iter().filter(|field| field.src_name() == src_name);
+        let field = matching.next();
+
+        if matching.any(|other| other.src_desc() != field.and_then(|field| field.src_desc())) {
+            bail!("Field {}/{} is ambiguous without a descriptor", owner, src_name);
+        }
+
+        Ok(field)
+    }
+
+    /// Finds a method by its owner's source name, its source name and its parameter count.
+    ///
+    /// This is useful for integrating with sources that only know the arity of a method.
+    /// The parameters are counted by parsing the source descriptors, and methods without
+    /// source descriptors are ignored. Returns `Ok(None)` if there's no such method,
+    /// and an error if multiple overloads match or a descriptor is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+    ///             c\ta\tcom/example/Foo\n\
+    ///             \tm\t(I)V\tfoo\tsetX\n\
+    ///             \tm\t(II)V\tfoo\tsetXY\n\
+    ///             \tm\t(J)V\tbar\tsetTime\n\
+    ///             \tm\t(I)V\tbar\tsetCount\n";
+    /// let mut tree = MappingTree::new();
+    /// Tiny2Reader::read_str(tiny, &mut tree)?;
+    ///
+    /// assert_eq!(tree.resolve_method("a", "foo", 1)?.unwrap().dst_name(0), Some("setX"));
+    /// assert_eq!(tree.resolve_method("a", "foo", 2)?.unwrap().dst_name(0), Some("setXY"));
+    /// assert!(tree.resolve_method("a", "foo", 3)?.is_none());
+    /// assert!(tree.resolve_method("a", "bar", 1).is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn resolve_method(&self, owner: &str, src_name: &str, param_count: usize) -> anyhow::Result<Option<&MethodEntry>> {
+        let class = match self.class(owner) {
+            Some(class) => class,
+            None => return Ok(None),
+        };
+
+        let mut found = None;
+
+        for method in class.methods() {
+            let src_desc = match method.src_desc() {
+                Some(src_desc) if method.src_name() == src_name => src_desc,
+                _ => continue,
+            };
+
+            if MethodDescriptor::parse(src_desc)?.params().len() == param_count {
+                if found.is_some() {
+                    bail!("Method {}/{} with {} parameters is ambiguous", owner, src_name, param_count);
+                }
+
+                found = Some(method);
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Returns the destination names of a class in all destination namespaces.
+    ///
+    /// The result is indexed like [`dst_namespaces`][Self::dst_namespaces] and contains `None` for namespaces
+    /// where the class has no name. The source name is not included, since it's the key used for the lookup.
+    /// Returns `None` if there's no such class.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tintermediary\tsrg\tnamed\n\
+    ///             c\ta\tclass_1\t\tcom/example/Foo\n\
+    ///             \tf\tI\tb\tfield_1\n\
+    ///             \tm\t()V\tc\t\tm_1_\trun\n";
+    /// let mut tree = MappingTree::new();
+    /// Tiny2Reader::read_str(tiny, &mut tree)?;
+    ///
+    /// assert_eq!(tree.class_all_names("a"), Some(vec![Some("class_1"), None, Some("com/example/Foo")]));
+    /// assert_eq!(tree.field_all_names("a", "b", Some("I")), Some(vec![Some("field_1"), None, None]));
+    /// assert_eq!(tree.method_all_names("a", "c", Some("()V")), Some(vec![None, Some("m_1_"), Some("run")]));
+    /// assert_eq!(tree.class_all_names("d"), None);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn class_all_names(&self, src_name: &str) -> Option<Vec<Option<&str>>> {
+        let class = self.class(src_name)?;
+        Some(self.all_dst_names(|namespace| class.dst_name(namespace)))
+    }
+
+    /// Returns the destination names of a field in all destination namespaces.
+    ///
+    /// The field is looked up like in [`ClassEntry::field`]. See [`class_all_names`][Self::class_all_names]
+    /// for the layout of the result.
+    pub fn field_all_names(&self, owner: &str, src_name: &str, src_desc: Option<&str>) -> Option<Vec<Option<&str>>> {
+        let field = self.class(owner)?.field(src_name, src_desc)?;
+        Some(self.all_dst_names(|namespace| field.dst_name(namespace)))
+    }
+
+    /// Returns the destination names of a method in all destination namespaces.
+    ///
+    /// The method is looked up like in [`ClassEntry::method`]. See [`class_all_names`][Self::class_all_names]
+    /// for the layout of the result.
+    pub fn method_all_names(&self, owner: &str, src_name: &str, src_desc: Option<&str>) -> Option<Vec<Option<&str>>> {
+        let method = self.class(owner)?.method(src_name, src_desc)?;
+        Some(self.all_dst_names(|namespace| method.dst_name(namespace)))
+    }
+
+    /// Finds a class by its name in the destination namespace at index `namespace`.
+    ///
+    /// Classes without a name in the namespace are found by their source names. The lookup tables
+    /// for all destination name queries are built on the first query and cached until the tree is modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+    ///             c\ta\tcom/example/Foo\n\
+    ///             \tf\tI\tb\tcount\n\
+    ///             \tm\t(La;)V\tc\tcopy\n";
+    /// let mut tree = MappingTree::new();
+    /// Tiny2Reader::read_str(tiny, &mut tree)?;
+    ///
+    /// assert_eq!(tree.class_by_dst(0, "com/example/Foo").unwrap().src_name(), "a");
+    /// assert!(tree.class_by_dst(0, "a").is_none());
+    ///
+    /// let method = tree.method_by_dst(0, "com/example/Foo", "copy", Some("(Lcom/example/Foo;)V")).unwrap();
+    /// assert_eq!((method.src_name(), method.src_desc()), ("c", Some("(La;)V")));
+    /// assert_eq!(tree.field_by_dst(0, "com/example/Foo", "count", None).unwrap().src_name(), "b");
+    ///
+    /// // Modifying the tree updates the results.
+    /// Tiny2Reader::read_str("tiny\t2\t0\tofficial\tnamed\nc\td\tcom/example/Bar\n", &mut tree)?;
+    /// assert_eq!(tree.class_by_dst(0, "com/example/Bar").unwrap().src_name(), "d");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn class_by_dst(&self, namespace: usize, dst_name: &str) -> Option<&ClassEntry> {
+        let index = self.dst_index().class(namespace, dst_name)?;
+        Some(&self.classes[index])
+    }
+
+    /// Finds a field by the destination names of its owner and itself in the namespace at index `namespace`.
+    ///
+    /// If a destination descriptor is given, only a field with that descriptor matches.
+    /// Otherwise the first field with the name is returned.
+    pub fn field_by_dst(&self, namespace: usize, owner_dst_name: &str, dst_name: &str, dst_desc: Option<&str>) -> Option<&FieldEntry> {
+        let dst_index = self.dst_index();
+        let class_index = dst_index.class(namespace, owner_dst_name)?;
+        let index = dst_index.field(namespace, class_index, dst_name, dst_desc)?;
+        Some(&self.classes[class_index].fields[index])
+    }
+
+    /// Finds a method by the destination names of its owner and itself in the namespace at index `namespace`.
+    ///
+    /// If a destination descriptor is given, only a method with that descriptor matches.
+    /// Otherwise the first method with the name is returned.
+    pub fn method_by_dst(&self, namespace: usize, owner_dst_name: &str, dst_name: &str, dst_desc: Option<&str>) -> Option<&MethodEntry> {
+        let dst_index = self.dst_index();
+        let class_index = dst_index.class(namespace, owner_dst_name)?;
+        let index = dst_index.method(namespace, class_index, dst_name, dst_desc)?;
+        Some(&self.classes[class_index].methods[index])
+    }
+
+    fn dst_index(&self) -> &DstIndex {
+        self.dst_index.get_or_init(|| DstIndex::build(self))
+    }
+
+    fn all_dst_names<'a>(&self, dst_name: impl Fn(usize) -> Option<&'a str>) -> Vec<Option<&'a str>> {
+        (0..self.dst_namespaces.len()).map(dst_name).collect()
+    }
+
+    /// Returns whether this tree contains no classes.
+    ///
+    /// Namespaces and metadata are not taken into account, so a tree read from
+    /// a file with just a header is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::srg::SrgReader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let mut tree = MappingTree::new();
+    /// assert!(tree.is_empty());
+    /// assert_eq!((tree.class_count(), tree.field_count(), tree.method_count()), (0, 0, 0));
+    ///
+    /// SrgReader::read_str("CL: a com/example/Foo\nFD: a/b com/example/Foo/value\n", &mut tree)?;
+    /// assert!(!tree.is_empty());
+    /// assert_eq!((tree.class_count(), tree.field_count(), tree.method_count()), (1, 1, 0));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// Returns the number of classes in this tree.
+    pub fn class_count(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Returns the number of fields across all classes in this tree.
+    pub fn field_count(&self) -> usize {
+        self.classes.iter().map(|class| class.fields.len()).sum()
+    }
+
+    /// Returns the number of methods across all classes in this tree.
+    pub fn method_count(&self) -> usize {
+        self.classes.iter().map(|class| class.methods.len()).sum()
+    }
+
+    /// Returns the number of elements with a non-empty destination name in the namespace at index `namespace`.
+    ///
+    /// Classes, fields, methods, arguments and variables are all counted, so the score can be used
+    /// to rank candidate mappings for the same source by how complete they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::srg::SrgReader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let mut sparse = MappingTree::new();
+    /// SrgReader::read_str("CL: a Foo\n", &mut sparse)?;
+    /// let mut complete = MappingTree::new();
+    /// SrgReader::read_str("CL: a Foo\nFD: a/b Foo/count\nMD: a/c ()V Foo/run ()V\n", &mut complete)?;
+    ///
+    /// assert_eq!(sparse.coverage_score(0), 1);
+    /// assert_eq!(complete.coverage_score(0), 3);
+    /// assert!(complete.coverage_score(0) > sparse.coverage_score(0));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn coverage_score(&self, namespace: usize) -> usize {
+        let is_named = |name: Option<&str>| name.is_some_and(|name| !name.is_empty());
+        let mut score = 0;
+
+        for class in &self.classes {
+            score += is_named(class.dst_name(namespace)) as usize;
+            score += class.fields.iter().filter(|field| is_named(field.dst_name(namespace))).count();
+
+            for method in &class.methods {
+                score += is_named(method.dst_name(namespace)) as usize;
+                score += method.args.iter().filter(|arg| is_named(arg.dst_name(namespace))).count();
+                score += method.vars.iter().filter(|var| is_named(var.dst_name(namespace))).count();
+            }
+        }
+
+        score
+    }
+
+    /// Returns the identities of all fields and methods in this tree.
+    ///
+    /// # Examples
+    ///
+    /// Finding the members that are present in two trees:
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::srg::SrgReader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let mut first = MappingTree::new();
+    /// SrgReader::read_str("FD: a/b a/value\nMD: a/c ()V a/run ()V\n", &mut first)?;
+    /// let mut second = MappingTree::new();
+    /// SrgReader::read_str("MD: a/c ()V a/execute ()V\nMD: a/c (I)V a/execute (I)V\n", &mut second)?;
+    ///
+    /// let second_keys = second.member_keys();
+    /// let common: Vec<_> = first.member_keys().intersection(&second_keys).cloned().collect();
+    /// assert_eq!(common.len(), 1);
+    /// assert_eq!((common[0].name.as_str(), common[0].desc.as_deref()), ("c", Some("()V")));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn member_keys(&self) -> HashSet<MemberKey> {
+        let mut keys = HashSet::new();
+
+        for class in &self.classes {
+            let owner = ClassName::from_internal_name(&class.src_name);
+
+            for field in &class.fields {
+                keys.insert(MemberKey {
+                    owner: owner.clone(),
+                    name: field.src_name.clone(),
+                    desc: field.src_desc.as_deref().map(str::to_owned),
+                    kind: MappedElementKind::Field,
+                });
+            }
+
+            for method in &class.methods {
+                keys.insert(MemberKey {
+                    owner: owner.clone(),
+                    name: method.src_name.clone(),
+                    desc: method.src_desc.as_deref().map(str::to_owned),
+                    kind: MappedElementKind::Method,
+                });
+            }
+        }
+
+        keys
+    }
+
+    fn class_or_insert(&mut self, src_name: &str) -> usize {
+        self.dst_index.take();
+
+        if let Some(&index) = self.class_indices.get(src_name) {
+            return index;
+        }
+
+        self.classes.push(ClassEntry::new(src_name));
+        let index = self.classes.len() - 1;
+        self.class_indices.insert(src_name.to_owned(), index);
+        index
+    }
+
+    /// Builds a tree from a sequence of [`MappingEvent`]s.
+    ///
+    /// This is a fallible counterpart to [`FromIterator`], which can't report errors:
+    /// events that arrive out of order, such as a field before any class, produce an error
+    /// instead of a panic. See [`try_extend`][Self::try_extend] for adding events to an existing tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::MappedElementKind;
+    /// use jvm_obfuscation_mappings::events::MappingEvent;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let events = vec![
+    ///     MappingEvent::Namespaces { src_namespace: "official".to_owned(), dst_namespaces: vec!["named".to_owned()] },
+    ///     MappingEvent::Class { src_name: "a".to_owned() },
+    ///     MappingEvent::DstName { target_kind: MappedElementKind::Class, namespace: 0, name: "com/example/Foo".to_owned() },
+    ///     MappingEvent::ElementContent { target_kind: MappedElementKind::Class },
+    ///     MappingEvent::Field { src_name: "b".to_owned(), src_desc: Some("I".to_owned()) },
+    ///     MappingEvent::DstName { target_kind: MappedElementKind::Field, namespace: 0, name: "count".to_owned() },
+    ///     MappingEvent::ElementContent { target_kind: MappedElementKind::Field },
+    ///     MappingEvent::End,
+    /// ];
+    /// let tree = MappingTree::try_from_events(events)?;
+    /// let class = tree.class("a").unwrap();
+    /// assert_eq!(class.dst_name(0), Some("com/example/Foo"));
+    /// assert_eq!(tree.field_count(), 1);
+    ///
+    /// let out_of_order = vec![MappingEvent::Field { src_name: "b".to_owned(), src_desc: None }];
+    /// assert!(MappingTree::try_from_events(out_of_order).is_err());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn try_from_events<I: IntoIterator<Item = MappingEvent>>(events: I) -> anyhow::Result<MappingTree> {
+        let mut tree = MappingTree::new();
+        tree.try_extend(events)?;
+        Ok(tree)
+    }
+
+    /// Visits a sequence of [`MappingEvent`]s with this tree, merging them into its contents.
+    ///
+    /// This is a fallible counterpart to [`Extend`]. The results of the visit methods are respected
+    /// like in [`EventVisitor`].
+    pub fn try_extend<I: IntoIterator<Item = MappingEvent>>(&mut self, events: I) -> anyhow::Result<()> {
+        let mut visitor = EventVisitor::new(self);
+
+        for (index, event) in events.into_iter().enumerate() {
+            visitor.visit_event(&event).with_context(|| format!("Invalid event at index {}: {:?}", index, event))?;
+        }
+
+        Ok(())
+    }
+
+    /// Merges another tree into this one.
+    ///
+    /// Both trees must have the same source namespace, and their destination namespaces must be disjoint.
+    /// The destination namespaces of `other` are appended after the ones of this tree, and elements
+    /// are aligned by their source names and descriptors. Elements that are only present in one of the trees
+    /// have no names in the namespaces of the other tree.
+    ///
+    /// If both trees contain a comment for the same element, the comment of this tree is kept.
+    /// Metadata properties of `other` are appended unless this tree already contains an equal property.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::MappedElementKind;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    /// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+    ///
+    /// let mut intermediary = MappingTree::new();
+    /// intermediary.visit_namespaces("official", &["intermediary"])?;
+    /// intermediary.visit_class("a")?;
+    /// intermediary.visit_dst_name(MappedElementKind::Class, 0, "net/minecraft/class_1")?;
+    /// intermediary.visit_element_content(MappedElementKind::Class)?;
+    /// intermediary.visit_end()?;
+    ///
+    /// let mut named = MappingTree::new();
+    /// named.visit_namespaces("official", &["named"])?;
+    /// named.visit_class("a")?;
+    /// named.visit_dst_name(MappedElementKind::Class, 0, "net/minecraft/Block")?;
+    /// named.visit_element_content(MappedElementKind::Class)?;
+    /// named.visit_class("b")?;
+    /// named.visit_dst_name(MappedElementKind::Class, 0, "net/minecraft/Item")?;
+    /// named.visit_element_content(MappedElementKind::Class)?;
+    /// named.visit_end()?;
+    ///
+    /// intermediary.merge_in(&named)?;
+    /// assert_eq!(intermediary.dst_namespaces(), &["intermediary", "named"]);
+    ///
+    /// let a = intermediary.class("a").unwrap();
+    /// assert_eq!(a.dst_name(0), Some("net/minecraft/class_1"));
+    /// assert_eq!(a.dst_name(1), Some("net/minecraft/Block"));
+    ///
+    /// let b = intermediary.class("b").unwrap();
+    /// assert_eq!(b.dst_name(0), None);
+    /// assert_eq!(b.dst_name(1), Some("net/minecraft/Item"));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn merge_in(&mut self, other: &MappingTree) -> anyhow::Result<()> {
+        if self.src_namespace != other.src_namespace {
+            bail!(
+                "Cannot merge trees with different source namespaces ({:?} and {:?})",
+                self.src_namespace, other.src_namespace
+            );
+        }
+
+        if let Some(namespace) = other.dst_namespaces.iter().find(|ns| self.dst_namespaces.contains(ns)) {
+            bail!("Cannot merge trees that share the destination namespace {}", namespace);
+        }
+
+        let offset = self.dst_namespaces.len();
+        self.dst_namespaces.extend(other.dst_namespaces.iter().cloned());
+        self.dst_index.take();
+
+        for property in &other.metadata {
+            if !self.metadata.contains(property) {
+                self.metadata.push(property.clone());
+            }
+        }
+
+        for other_class in &other.classes {
+            let class_index = self.class_or_insert(&other_class.src_name);
+            let class = &mut self.classes[class_index];
+            merge_element(&mut class.dst_names, &mut class.comment, &other_class.dst_names, &other_class.comment, offset);
+
+            for other_field in &other_class.fields {
+                let field_index = class.field_or_insert(&other_field.src_name, other_field.src_desc.as_deref(), &mut self.desc_interner);
+                let field = &mut class.fields[field_index];
+                merge_element(&mut field.dst_names, &mut field.comment, &other_field.dst_names, &other_field.comment, offset);
+            }
+
+            for other_method in &other_class.methods {
+                let method_index = class.method_or_insert(&other_method.src_name, other_method.src_desc.as_deref(), &mut self.desc_interner);
+                let method = &mut class.methods[method_index];
+                merge_element(&mut method.dst_names, &mut method.comment, &other_method.dst_names, &other_method.comment, offset);
+
+                for other_arg in &other_method.args {
+                    let arg_index = method.arg_or_insert(other_arg.arg_position, other_arg.lv_index, other_arg.src_name.as_deref());
+                    let arg = &mut method.args[arg_index];
+                    merge_element(&mut arg.dst_names, &mut arg.comment, &other_arg.dst_names, &other_arg.comment, offset);
+                }
+
+                for other_var in &other_method.vars {
+                    let var_index = method.var_or_insert(
+                        other_var.lvt_row_index, other_var.lv_index, other_var.start_op_idx, other_var.src_name.as_deref()
+                    );
+                    let var = &mut method.vars[var_index];
+                    merge_element(&mut var.dst_names, &mut var.comment, &other_var.dst_names, &other_var.comment, offset);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads mappings in the format `format` into a new tree.
+    ///
+    /// The namespaces are named like in the `read` function of the format's reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::MappingFormat;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let tiny = "tiny\t2\t0\tofficial\tintermediary\tnamed\n\
+    ///             c\ta\tclass_1\tcom/example/Foo\n";
+    /// let tree = MappingTree::read(tiny.as_bytes(), MappingFormat::Tiny2)?;
+    /// assert_eq!(tree.class("a").unwrap().dst_name(1), Some("com/example/Foo"));
+    ///
+    /// let mut srg = String::new();
+    /// tree.write(&mut srg, MappingFormat::Srg, "official", "named")?;
+    /// assert_eq!(srg, "CL: a com/example/Foo\n");
+    ///
+    /// let error = tree.write(String::new(), MappingFormat::ProGuard, "official", "named").unwrap_err();
+    /// assert_eq!(error.to_string(), "Writing ProGuard mappings isn't supported");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn read<R: BufRead>(input: R, format: MappingFormat) -> anyhow::Result<MappingTree> {
+        let mut tree = MappingTree::new();
+
+        match format {
+            MappingFormat::Tiny2 => Tiny2Reader::read(input, &mut tree)?,
+            MappingFormat::Srg => SrgReader::read(input, &mut tree)?,
+            MappingFormat::EnigmaSingleFile => EnigmaSingleFileReader::read(input, &mut tree)?,
+            MappingFormat::ProGuard => ProGuardReader::read(input, &mut tree)?,
+        }
+
+        Ok(tree)
+    }
+
+    /// Writes the mappings from the namespace `src_namespace` to the namespace `dst_namespace` in the format `format`.
+    ///
+    /// The other destination namespaces of this tree are skipped while writing, without copying the tree. `src_namespace` has to be
+    /// the source namespace of this tree, since switching the source namespace isn't supported.
+    /// See [`read`][Self::read] for an example.
+    pub fn write<W: std::fmt::Write>(&self, out: W, format: MappingFormat, src_namespace: &str, dst_namespace: &str) -> anyhow::Result<()> {
+        if format == MappingFormat::ProGuard {
+            bail!("Writing ProGuard mappings isn't supported");
+        }
+
+        if self.src_namespace() != Some(src_namespace) {
+            bail!("Cannot write from {}, the source namespace of the tree is {:?}", src_namespace, self.src_namespace());
+        }
+
+        let namespace = self.dst_namespace_index(dst_namespace)
+            .ok_or_else(|| anyhow!("Unknown destination namespace {}", dst_namespace))?;
+
+        match format {
+            MappingFormat::Tiny2 => self.accept(&mut SingleNamespaceVisitor::new(Tiny2Writer::new(out), namespace)),
+            MappingFormat::Srg => self.accept(&mut SingleNamespaceVisitor::new(SrgWriter::new(out), namespace)),
+            MappingFormat::EnigmaSingleFile => self.accept(&mut SingleNamespaceVisitor::new(EnigmaSingleFileWriter::new(out), namespace)),
+            MappingFormat::ProGuard => unreachable!("ProGuard output is rejected above"),
+        }
+    }
+
+    /// Replays the contents of this tree into a visitor.
+    ///
+    /// Additional passes are made as long as the visitor requests them in [`MappingVisitor::visit_end`].
+    /// If the visitor needs destination descriptors, they are computed by remapping the source descriptors
+    /// using the class mappings of this tree. Each distinct descriptor is only remapped once per namespace
+    /// during an `accept` call.
+    pub fn accept<V: MappingVisitor + ?Sized>(&self, visitor: &mut V) -> VisitResult<()> {
+        self.accept_in_order(visitor, false)
+    }
+
+    /// Replays the contents of this tree into a visitor like [`accept`][Self::accept],
+    /// but in a deterministic order independent of insertion.
+    ///
+    /// Classes are emitted sorted by their source names, and the fields and methods of each class
+    /// sorted by their source names and descriptors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::format::srg::{SrgReader, SrgWriter};
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let mut first = MappingTree::new();
+    /// SrgReader::read_str("CL: b Bar\nCL: a Foo\nFD: a/d Foo/count\nFD: a/c Foo/size\n", &mut first)?;
+    /// let mut second = MappingTree::new();
+    /// SrgReader::read_str("FD: a/c Foo/size\nCL: a Foo\nCL: b Bar\nFD: a/d Foo/count\n", &mut second)?;
+    ///
+    /// let mut first_output = String::new();
+    /// first.accept_sorted(&mut SrgWriter::new(&mut first_output))?;
+    /// let mut second_output = String::new();
+    /// second.accept_sorted(&mut SrgWriter::new(&mut second_output))?;
+    /// assert_eq!(first_output, second_output);
+    /// assert!(first_output.find("CL: a").unwrap() < first_output.find("CL: b").unwrap());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn accept_sorted<V: MappingVisitor + ?Sized>(&self, visitor: &mut V) -> VisitResult<()> {
+        self.accept_in_order(visitor, true)
+    }
+
+    fn accept_in_order<V: MappingVisitor + ?Sized>(&self, visitor: &mut V, sorted: bool) -> VisitResult<()> {
+        let src_namespace = self.src_namespace.as_deref()
+            .ok_or_else(|| anyhow!("Cannot accept a mapping tree without namespaces"))?;
+        let dst_namespaces: Vec<&str> = self.dst_namespaces.iter().map(String::as_str).collect();
+        let flags = visitor.flags();
+        let mut descs = DstDescs {
+            tree: self,
+            fields: flags.contains(&MappingFlag::NeedsDstFieldDesc),
+            methods: flags.contains(&MappingFlag::NeedsDstMethodDesc),
+            cache: HashMap::new(),
+        };
+
+        loop {
+            if visitor.visit_header()? {
+                visitor.visit_namespaces(src_namespace, &dst_namespaces)?;
+
+                for (key, value) in &self.metadata {
+                    visitor.visit_metadata(key, value)?;
+                }
+            }
+
+            if visitor.visit_content()? {
+                visitor.visit_content_size(self.classes.len())?;
+                let mut classes: Vec<&ClassEntry> = self.classes.iter().collect();
+
+                if sorted {
+                    classes.sort_by(|a, b| a.src_name.cmp(&b.src_name));
+                }
+
+                for class in classes {
+                    accept_class(class, sorted, &mut descs, visitor)?;
+                }
+            }
+
+            if visitor.visit_end()? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Remaps a source descriptor to the destination namespace `namespace` using the class mappings of this tree.
+    fn map_desc(&self, desc: &str, namespace: usize) -> String {
+        remap_descriptor(desc, |class| {
+            self.class(class).and_then(|class| class.dst_name(namespace)).map(str::to_owned)
+        })
+    }
+
+    fn current_class_mut(&mut self) -> VisitResult<&mut ClassEntry> {
+        let index = self.current_class.ok_or_else(|| anyhow!("No class is being visited"))?;
+        Ok(&mut self.classes[index])
+    }
+
+    fn current_field_mut(&mut self) -> VisitResult<&mut FieldEntry> {
+        let index = self.current_field.ok_or_else(|| anyhow!("No field is being visited"))?;
+        Ok(&mut self.current_class_mut()?.fields[index])
+    }
+
+    fn current_method_mut(&mut self) -> VisitResult<&mut MethodEntry> {
+        let index = self.current_method.ok_or_else(|| anyhow!("No method is being visited"))?;
+        Ok(&mut self.current_class_mut()?.methods[index])
+    }
+
+    fn current_arg_mut(&mut self) -> VisitResult<&mut MethodArgEntry> {
+        let index = self.current_arg.ok_or_else(|| anyhow!("No method argument is being visited"))?;
+        Ok(&mut self.current_method_mut()?.args[index])
+    }
+
+    fn current_var_mut(&mut self) -> VisitResult<&mut MethodVarEntry> {
+        let index = self.current_var.ok_or_else(|| anyhow!("No method variable is being visited"))?;
+        Ok(&mut self.current_method_mut()?.vars[index])
+    }
+
+    /// Returns the destination names and the comment of the current element of the kind `target_kind`.
+    fn current_element_mut(&mut self, target_kind: MappedElementKind) -> VisitResult<(&mut Vec<Option<String>>, &mut Option<String>)> {
+        Ok(match target_kind {
+            MappedElementKind::Class => {
+                let class = self.current_class_mut()?;
+                (&mut class.dst_names, &mut class.comment)
+            }
+            MappedElementKind::Field => {
+                let field = self.current_field_mut()?;
+                (&mut field.dst_names, &mut field.comment)
+            }
+            MappedElementKind::Method => {
+                let method = self.current_method_mut()?;
+                (&mut method.dst_names, &mut method.comment)
+            }
+            MappedElementKind::MethodArg => {
+                let arg = self.current_arg_mut()?;
+                (&mut arg.dst_names, &mut arg.comment)
+            }
+            MappedElementKind::MethodVar => {
+                let var = self.current_var_mut()?;
+                (&mut var.dst_names, &mut var.comment)
+            }
+        })
+    }
+}
+
+/// Trees are equal if they have the same namespaces, metadata and classes, regardless of their visitation state.
+impl PartialEq for MappingTree {
+    fn eq(&self, other: &Self) -> bool {
+        self.src_namespace == other.src_namespace
+            && self.dst_namespaces == other.dst_namespaces
+            && self.metadata == other.metadata
+            && self.classes == other.classes
+    }
+}
+
+impl Eq for MappingTree {}
+
+/// Copies the destination names of an element into another one, shifting the namespaces by `offset`.
+/// The comment is only copied if the target doesn't have one yet.
+fn merge_element(
+    dst_names: &mut Vec<Option<String>>,
+    comment: &mut Option<String>,
+    other_dst_names: &[Option<String>],
+    other_comment: &Option<String>,
+    offset: usize,
+) {
+    for (namespace, name) in other_dst_names.iter().enumerate() {
+        if name.is_some() {
+            set_dst_name(dst_names, offset + namespace, name.clone());
+        }
+    }
+
+    if comment.is_none() {
+        comment.clone_from(other_comment);
+    }
+}
+
+/// The destination descriptors requested by a visitor during [`MappingTree::accept`].
+struct DstDescs<'a> {
+    tree: &'a MappingTree,
+    fields: bool,
+    methods: bool,
+    /// Remapped descriptors keyed by the source descriptor and the destination namespace.
+    cache: HashMap<(&'a str, usize), String>,
+}
+
+impl<'a> DstDescs<'a> {
+    /// Visits the destination descriptors of a member in all destination namespaces if they're requested.
+    fn accept<V: MappingVisitor + ?Sized>(&mut self, visitor: &mut V, target_kind: MappedElementKind, src_desc: Option<&'a str>) -> VisitResult<()> {
+        let requested = match target_kind {
+            MappedElementKind::Field => self.fields,
+            MappedElementKind::Method => self.methods,
+            _ => false,
+        };
+
+        if let (true, Some(src_desc)) = (requested, src_desc) {
+            for namespace in 0..self.tree.dst_namespaces.len() {
+                let tree = self.tree;
+                let dst_desc = self.cache.entry((src_desc, namespace))
+                    .or_insert_with(|| tree.map_desc(src_desc, namespace));
+                visitor.visit_dst_desc(target_kind, namespace, dst_desc)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Visits the destination names, element content and comment of an element.
+///
+/// Returns whether the children of the element should be visited.
+fn accept_element<V: MappingVisitor + ?Sized>(
+    visitor: &mut V,
+    target_kind: MappedElementKind,
+    dst_names: &[Option<String>],
+    comment: Option<&str>,
+) -> VisitResult<bool> {
+    for (namespace, name) in dst_names.iter().enumerate() {
+        if let Some(name) = name {
+            visitor.visit_dst_name(target_kind, namespace, name)?;
+        }
+    }
+
+    if !visitor.visit_element_content(target_kind)? {
+        return Ok(false);
+    }
+
+    if let Some(comment) = comment {
+        visitor.visit_comment(target_kind, comment)?;
+    }
+
+    Ok(true)
+}
+
+fn accept_class<'a, V: MappingVisitor + ?Sized>(
+    class: &'a ClassEntry,
+    sorted: bool,
+    descs: &mut DstDescs<'a>,
+    visitor: &mut V,
+) -> VisitResult<()> {
+    if !visitor.visit_class(&class.src_name)?
+        || !accept_element(visitor, MappedElementKind::Class, &class.dst_names, class.comment())? {
+        return Ok(());
+    }
+
+    let mut fields: Vec<&FieldEntry> = class.fields.iter().collect();
+    let mut methods: Vec<&MethodEntry> = class.methods.iter().collect();
+
+    if sorted {
+        fields.sort_by(|a, b| (&a.src_name, a.src_desc()).cmp(&(&b.src_name, b.src_desc())));
+        methods.sort_by(|a, b| (&a.src_name, a.src_desc()).cmp(&(&b.src_name, b.src_desc())));
+    }
+
+    for field in fields {
+        if visitor.visit_field(&field.src_name, field.src_desc())? {
+            descs.accept(visitor, MappedElementKind::Field, field.src_desc())?;
+            accept_element(visitor, MappedElementKind::Field, &field.dst_names, field.comment())?;
+        }
+    }
+
+    for method in methods {
+        if !visitor.visit_method(&method.src_name, method.src_desc())? {
+            continue;
+        }
+
+        descs.accept(visitor, MappedElementKind::Method, method.src_desc())?;
+
+        if !accept_element(visitor, MappedElementKind::Method, &method.dst_names, method.comment())? {
+            continue;
+        }
+
+        for arg in &method.args {
+            if visitor.visit_method_arg(arg.arg_position, arg.lv_index, arg.src_name())? {
+                accept_element(visitor, MappedElementKind::MethodArg, &arg.dst_names, arg.comment())?;
+            }
+        }
+
+        for var in &method.vars {
+            if visitor.visit_method_var(var.lvt_row_index, var.lv_index, var.start_op_idx, var.src_name())? {
+                accept_element(visitor, MappedElementKind::MethodVar, &var.dst_names, var.comment())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl MappingVisitor for MappingTree {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        HashSet::new()
+    }
+
+    fn reset(&mut self) {
+        self.dst_namespace_map.clear();
+        self.current_class = None;
+        self.current_field = None;
+        self.current_method = None;
+        self.current_arg = None;
+        self.current_var = None;
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.dst_index.take();
+
+        match &self.src_namespace {
+            None => self.src_namespace = Some(src_namespace.to_owned()),
+            Some(existing) if existing == src_namespace => {}
+            Some(existing) => bail!("Source namespace {} doesn't match the tree's source namespace {}", src_namespace, existing),
+        }
+
+        self.dst_namespace_map = dst_namespaces.iter()
+            .map(|&namespace| match self.dst_namespace_index(namespace) {
+                Some(index) => index,
+                None => {
+                    self.dst_namespaces.push(namespace.to_owned());
+                    self.dst_namespaces.len() - 1
+                }
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        let property = (key.to_owned(), value.to_owned());
+
+        if !self.metadata.contains(&property) {
+            self.metadata.push(property);
+        }
+
+        Ok(())
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.current_class = Some(self.class_or_insert(src_name));
+        self.current_field = None;
+        self.current_method = None;
+        self.current_arg = None;
+        self.current_var = None;
+        Ok(true)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.dst_index.take();
+        let class_index = self.current_class.ok_or_else(|| anyhow!("No class is being visited"))?;
+        let index = self.classes[class_index].field_or_insert(src_name, src_desc, &mut self.desc_interner);
+        self.current_field = Some(index);
+        Ok(true)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.dst_index.take();
+        let class_index = self.current_class.ok_or_else(|| anyhow!("No class is being visited"))?;
+        let index = self.classes[class_index].method_or_insert(src_name, src_desc, &mut self.desc_interner);
+        self.current_method = Some(index);
+        self.current_arg = None;
+        self.current_var = None;
+        Ok(true)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        let index = self.current_method_mut()?.arg_or_insert(arg_position, lv_index, src_name);
+        self.current_arg = Some(index);
+        Ok(true)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        let index = self.current_method_mut()?.var_or_insert(lvt_row_index, lv_index, start_op_idx, src_name);
+        self.current_var = Some(index);
+        Ok(true)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        self.reset();
+        Ok(true)
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        let namespace = *self.dst_namespace_map.get(namespace)
+            .ok_or_else(|| anyhow!("Unknown destination namespace index {}", namespace))?;
+        self.dst_index.take();
+        let (dst_names, _) = self.current_element_mut(target_kind)?;
+
+        if dst_names.get(namespace).is_none_or(|existing| existing.as_deref().is_none_or(str::is_empty)) {
+            set_dst_name(dst_names, namespace, Some(name.to_owned()));
+        }
+
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, _target_kind: MappedElementKind) -> VisitResult<bool> {
+        Ok(true)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        let (_, current_comment) = self.current_element_mut(target_kind)?;
+
+        if current_comment.as_deref().is_none_or(str::is_empty) {
+            *current_comment = Some(comment.to_owned());
+        }
+
+        Ok(())
+    }
+}
+
@@ -0,0 +1,109 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::BTreeSet;
+use anyhow::bail;
+use crate::descriptor::remap_descriptor;
+use super::MappingTree;
+
+/// A checker for classes that are referenced in descriptors but not mapped in a [`MappingTree`].
+///
+/// All class references in the source descriptors of fields and methods are collected, and those
+/// without a class entry in the tree are reported. Classes starting with one of the ignored prefixes
+/// are skipped, which by default are `java/` and `javax/`.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::{MappingTree, ReferentialIntegrityChecker};
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tm\t(La;Ljava/lang/String;Lb;)V\tc\trun\n\
+///             \tf\t[Lcom/example/Missing;\td\tmissing\n";
+/// let mut tree = MappingTree::new();
+/// Tiny2Reader::read_str(tiny, &mut tree)?;
+///
+/// let checker = ReferentialIntegrityChecker::new();
+/// assert_eq!(checker.find_unmapped(&tree), vec!["b".to_owned(), "com/example/Missing".to_owned()]);
+/// let error = checker.check(&tree).unwrap_err();
+/// assert_eq!(error.to_string(), "Unmapped classes referenced in descriptors: b, com/example/Missing");
+///
+/// let checker = ReferentialIntegrityChecker::new().with_ignored_prefixes(&["java/", "com/"]);
+/// assert_eq!(checker.find_unmapped(&tree), vec!["b".to_owned()]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReferentialIntegrityChecker {
+    ignored_prefixes: Vec<String>,
+}
+
+impl ReferentialIntegrityChecker {
+    /// Creates a new checker that ignores classes in `java/` and `javax/`.
+    pub fn new() -> ReferentialIntegrityChecker {
+        ReferentialIntegrityChecker { ignored_prefixes: vec!["java/".to_owned(), "javax/".to_owned()] }
+    }
+
+    /// Replaces the prefixes of the internal names of classes that don't need to be mapped.
+    pub fn with_ignored_prefixes(mut self, prefixes: &[&str]) -> Self {
+        self.ignored_prefixes = prefixes.iter().map(|&prefix| prefix.to_owned()).collect();
+        self
+    }
+
+    /// Finds the unmapped classes referenced in the descriptors of a tree, sorted by their internal names.
+    pub fn find_unmapped(&self, tree: &MappingTree) -> Vec<String> {
+        let mut unmapped = BTreeSet::new();
+
+        for class in tree.classes() {
+            let field_descs = class.fields().iter().filter_map(|field| field.src_desc());
+            let method_descs = class.methods().iter().filter_map(|method| method.src_desc());
+
+            for desc in field_descs.chain(method_descs) {
+                remap_descriptor(desc, |name| {
+                    let ignored = self.ignored_prefixes.iter().any(|prefix| name.starts_with(prefix.as_str()));
+
+                    if !ignored && tree.class(name).is_none() {
+                        unmapped.insert(name.to_owned());
+                    }
+
+                    None
+                });
+            }
+        }
+
+        unmapped.into_iter().collect()
+    }
+
+    /// Checks that all classes referenced in the descriptors of a tree are mapped.
+    ///
+    /// The error message lists the unmapped classes.
+    pub fn check(&self, tree: &MappingTree) -> anyhow::Result<()> {
+        let unmapped = self.find_unmapped(tree);
+
+        if !unmapped.is_empty() {
+            bail!("Unmapped classes referenced in descriptors: {}", unmapped.join(", "));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ReferentialIntegrityChecker {
+    fn default() -> Self {
+        ReferentialIntegrityChecker::new()
+    }
+}
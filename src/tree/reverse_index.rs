@@ -0,0 +1,212 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use crate::MappedElementKind;
+use crate::visitor::{MappingFlag, MappingVisitor, VisitResult};
+
+/// The source identity of a field or method found in a [`ReverseIndex`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MemberSource {
+    owner: String,
+    name: String,
+    desc: Option<String>,
+}
+
+impl MemberSource {
+    /// Returns the source name of the class that owns the member.
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// Returns the source name of the member.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the source descriptor of the member.
+    pub fn desc(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+}
+
+type IndexKey = (usize, String, String, Option<String>);
+
+/// A visitor that builds a lookup index from destination names back to source names.
+///
+/// Classes are indexed by their destination names, and fields and methods by the destination names
+/// of their owner and themselves, along with their destination descriptors.
+/// Elements without a destination name in a namespace are indexed by their source name.
+///
+/// Member lookups require destination descriptors, so the index should be filled
+/// by a producer that supports them, such as [`MappingTree::accept`](super::MappingTree::accept).
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+/// use jvm_obfuscation_mappings::tree::{MappingTree, ReverseIndex};
+///
+/// let tiny = "tiny\tv2\t0\tofficial\tnamed\n\
+///             c\ta\tcom/example/Foo\n\
+///             \tm\t(La;)V\tb\tcopy\n";
+/// let mut tree = MappingTree::new();
+/// Tiny2Reader::read_str(tiny, &mut tree)?;
+/// let mut index = ReverseIndex::new();
+/// tree.accept(&mut index)?;
+///
+/// assert_eq!(index.class(0, "com/example/Foo"), Some("a"));
+/// let method = index.method(0, "com/example/Foo", "copy", Some("(Lcom/example/Foo;)V")).unwrap();
+/// assert_eq!((method.owner(), method.name(), method.desc()), ("a", "b", Some("(La;)V")));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ReverseIndex {
+    classes: HashMap<(usize, String), String>,
+    fields: HashMap<IndexKey, MemberSource>,
+    methods: HashMap<IndexKey, MemberSource>,
+    dst_count: usize,
+    class_src_name: String,
+    class_dst_names: Vec<Option<String>>,
+    member_src_name: String,
+    member_src_desc: Option<String>,
+    dst_names: Vec<Option<String>>,
+    dst_descs: Vec<Option<String>>,
+}
+
+impl ReverseIndex {
+    /// Creates a new, empty reverse index.
+    pub fn new() -> ReverseIndex {
+        ReverseIndex::default()
+    }
+
+    /// Finds the source name of a class by its destination name in the namespace `namespace`.
+    pub fn class(&self, namespace: usize, dst_name: &str) -> Option<&str> {
+        self.classes.get(&(namespace, dst_name.to_owned())).map(String::as_str)
+    }
+
+    /// Finds the source of a field by its destination owner, name and descriptor in the namespace `namespace`.
+    pub fn field(&self, namespace: usize, owner: &str, name: &str, desc: Option<&str>) -> Option<&MemberSource> {
+        self.fields.get(&member_key(namespace, owner, name, desc))
+    }
+
+    /// Finds the source of a method by its destination owner, name and descriptor in the namespace `namespace`.
+    pub fn method(&self, namespace: usize, owner: &str, name: &str, desc: Option<&str>) -> Option<&MemberSource> {
+        self.methods.get(&member_key(namespace, owner, name, desc))
+    }
+
+    fn visit_member(&mut self, src_name: &str, src_desc: Option<&str>) {
+        self.member_src_name = src_name.to_owned();
+        self.member_src_desc = src_desc.map(str::to_owned);
+        self.dst_names = vec![None; self.dst_count];
+        self.dst_descs = vec![None; self.dst_count];
+    }
+}
+
+fn member_key(namespace: usize, owner: &str, name: &str, desc: Option<&str>) -> IndexKey {
+    (namespace, owner.to_owned(), name.to_owned(), desc.map(str::to_owned))
+}
+
+impl MappingVisitor for ReverseIndex {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        HashSet::from([MappingFlag::NeedsDstFieldDesc, MappingFlag::NeedsDstMethodDesc])
+    }
+
+    fn visit_namespaces(&mut self, _src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        self.dst_count = dst_namespaces.len();
+        Ok(())
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        self.class_src_name = src_name.to_owned();
+        self.dst_names = vec![None; self.dst_count];
+        Ok(true)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.visit_member(src_name, src_desc);
+        Ok(true)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        self.visit_member(src_name, src_desc);
+        Ok(true)
+    }
+
+    fn visit_method_arg(&mut self, _arg_position: i32, _lv_index: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_method_var(&mut self, _lvt_row_index: i32, _lv_index: i32, _start_op_idx: i32, _src_name: Option<&str>) -> VisitResult<bool> {
+        Ok(false)
+    }
+
+    fn visit_dst_name(&mut self, _target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        if let Some(dst_name) = self.dst_names.get_mut(namespace) {
+            *dst_name = Some(name.to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn visit_dst_desc(&mut self, _target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        if let Some(dst_desc) = self.dst_descs.get_mut(namespace) {
+            *dst_desc = Some(desc.to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        match target_kind {
+            MappedElementKind::Class => {
+                self.class_dst_names = std::mem::take(&mut self.dst_names);
+
+                for (namespace, dst_name) in self.class_dst_names.iter().enumerate() {
+                    let dst_name = dst_name.as_deref().unwrap_or(&self.class_src_name);
+                    self.classes.insert((namespace, dst_name.to_owned()), self.class_src_name.clone());
+                }
+            }
+            MappedElementKind::Field | MappedElementKind::Method => {
+                let source = MemberSource {
+                    owner: self.class_src_name.clone(),
+                    name: self.member_src_name.clone(),
+                    desc: self.member_src_desc.clone(),
+                };
+
+                for namespace in 0..self.dst_count {
+                    let owner = self.class_dst_names.get(namespace).cloned().flatten()
+                        .unwrap_or_else(|| self.class_src_name.clone());
+                    let name = self.dst_names[namespace].clone().unwrap_or_else(|| self.member_src_name.clone());
+                    let key = (namespace, owner, name, self.dst_descs[namespace].clone());
+
+                    if target_kind == MappedElementKind::Field {
+                        self.fields.insert(key, source.clone());
+                    } else {
+                        self.methods.insert(key, source.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(true)
+    }
+
+    fn visit_comment(&mut self, _target_kind: MappedElementKind, _comment: &str) -> VisitResult<()> {
+        Ok(())
+    }
+}
@@ -21,7 +21,7 @@ use crate::MappedElementKind;
 pub type VisitResult<T> = anyhow::Result<T>;
 
 /// Flags that describe the behaviour of a mapping visitor.
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MappingFlag {
     /// Indication that the visitor may require multiple passes.
     NeedsMultiplePasses,
@@ -42,6 +42,29 @@ pub enum MappingFlag {
     NeedsDstMethodDesc
 }
 
+/// Returns the flags required by `consumer` that are not in `producer_capabilities`.
+///
+/// `producer_capabilities` contains the requirements that a mapping producer, such as a reader,
+/// is able to satisfy. If the result is not empty, the consumer can't be fed by the producer directly.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use jvm_obfuscation_mappings::format::tiny2::Tiny2Writer;
+/// use jvm_obfuscation_mappings::visitor::{missing_flags, MappingFlag};
+///
+/// let producer = HashSet::from([MappingFlag::NeedsHeaderMetadata, MappingFlag::NeedsUniqueness]);
+/// let writer = Tiny2Writer::new(String::new());
+/// assert_eq!(
+///     missing_flags(&producer, &writer),
+///     HashSet::from([MappingFlag::NeedsSrcFieldDesc, MappingFlag::NeedsSrcMethodDesc])
+/// );
+/// ```
+pub fn missing_flags(producer_capabilities: &HashSet<MappingFlag>, consumer: &impl MappingVisitor) -> HashSet<MappingFlag> {
+    consumer.flags().difference(producer_capabilities).copied().collect()
+}
+
 /// Visitor with order implied context and consecutive dst name visits.
 ///
 /// The visitation order is as follows (omitting visit prefixes for brevity, lowercase for cross references):
@@ -70,6 +93,22 @@ pub trait MappingVisitor {
     /// Returns the flags describing this mapping visitor.
     fn flags(&self) -> HashSet<MappingFlag>;
 
+    /// Returns whether this visitor can store comments.
+    ///
+    /// Visitors that drop all comments, such as writers for formats without comments, return `false`.
+    /// Visitors that wrap another visitor return its result. By default, returns `true`.
+    fn supports_comments(&self) -> bool {
+        true
+    }
+
+    /// Returns whether this visitor can store metadata properties.
+    ///
+    /// Visitors that drop all metadata, such as writers for formats without metadata, return `false`.
+    /// Visitors that wrap another visitor return its result. By default, returns `true`.
+    fn supports_metadata(&self) -> bool {
+        true
+    }
+
     /// Reset the visitor including any chained visitors to allow for another independent visit (excluding visitEnd=false).
     fn reset(&mut self) {}
 
@@ -89,7 +128,7 @@ pub trait MappingVisitor {
     ///
     /// Some mapping formats allow you to add a set of key-value properties
     /// which will be passed to this method.
-    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+    fn visit_metadata(&mut self, _key: &str, _value: &str) -> VisitResult<()> {
         Ok(())
     }
 
@@ -100,6 +139,46 @@ pub trait MappingVisitor {
         Ok(true)
     }
 
+    /// Visits the number of classes in the mapping content before the first class.
+    ///
+    /// This is only called by producers that know the count in advance, such as
+    /// [`MappingTree::accept`](crate::tree::MappingTree::accept), after [`visit_content`][Self::visit_content]
+    /// has returned `true`. It is useful for preallocating buffers or reporting progress.
+    ///
+    /// By default, does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jvm_obfuscation_mappings::events::{EventSink, MappingEvent};
+    /// use jvm_obfuscation_mappings::format::srg::SrgReader;
+    /// use jvm_obfuscation_mappings::tree::MappingTree;
+    ///
+    /// let mut tree = MappingTree::new();
+    /// SrgReader::read_str("CL: a com/example/Foo\nCL: b com/example/Bar\n", &mut tree)?;
+    /// let mut sink = EventSink::new();
+    /// tree.accept(&mut sink)?;
+    ///
+    /// let content = sink.events().iter().position(|event| *event == MappingEvent::Content).unwrap();
+    /// assert_eq!(sink.events()[content + 1], MappingEvent::ContentSize { classes: 2 });
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    fn visit_content_size(&mut self, _classes: usize) -> VisitResult<()> {
+        Ok(())
+    }
+
+    /// Visits the line of the input that the following elements were read from.
+    ///
+    /// Readers that visit elements directly call this before each line of the mapping content,
+    /// so that visitors can point back at the input in their error messages. Readers that
+    /// buffer the mappings in a [`MappingTree`](crate::tree::MappingTree) before visiting them
+    /// don't report any positions. Lines are numbered starting from 1.
+    ///
+    /// By default, does nothing.
+    fn visit_source_position(&mut self, _line: usize) -> VisitResult<()> {
+        Ok(())
+    }
+
     /// Visits a class and its source name.
     ///
     /// The result describes whether the rest of the class (destination names, members and comments) should be read.
@@ -142,7 +221,7 @@ pub trait MappingVisitor {
     ///
     /// `namespace` is the namespace index or index into the `dst_namespaces` list
     /// in [`Self::visit_namespaces`].
-    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+    fn visit_dst_desc(&mut self, _target_kind: MappedElementKind, _namespace: usize, _desc: &str) -> VisitResult<()> {
         Ok(())
     }
 
@@ -158,3 +237,208 @@ pub trait MappingVisitor {
     /// The `comment` can potentially be a multi-line string.
     fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()>;
 }
+
+/// Forwards all visits to the referenced visitor.
+///
+/// This makes it possible to pass a borrowed visitor to adapters that take ownership of their
+/// wrapped visitor, and keep using it afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::adapter::LimitVisitor;
+/// use jvm_obfuscation_mappings::format::srg::SrgReader;
+/// use jvm_obfuscation_mappings::tree::MappingTree;
+///
+/// let mut tree = MappingTree::new();
+/// SrgReader::read_str("CL: a com/example/Foo\n", &mut LimitVisitor::new(&mut tree).with_max_classes(10))?;
+/// SrgReader::read_str("CL: b com/example/Bar\n", &mut LimitVisitor::new(&mut tree).with_max_classes(10))?;
+/// assert_eq!(tree.class_count(), 2);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+impl<V: MappingVisitor + ?Sized> MappingVisitor for &mut V {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        (**self).flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        (**self).supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        (**self).supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        (**self).visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        (**self).visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        (**self).visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        (**self).visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        (**self).visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        (**self).visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        (**self).visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        (**self).visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        (**self).visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        (**self).visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        (**self).visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        (**self).visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        (**self).visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        (**self).visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        (**self).visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        (**self).visit_comment(target_kind, comment)
+    }
+}
+
+/// Forwards all visits to the boxed visitor.
+///
+/// This allows choosing visitors at runtime using `Box<dyn MappingVisitor>`.
+///
+/// # Examples
+///
+/// ```
+/// use jvm_obfuscation_mappings::format::srg::SrgWriter;
+/// use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+/// use jvm_obfuscation_mappings::visitor::MappingVisitor;
+///
+/// let tiny = "tiny\t2\t0\tofficial\tnamed\nc\ta\tcom/example/Foo\n";
+/// let output_format = "srg";
+/// let mut output = String::new();
+/// let mut writer: Box<dyn MappingVisitor> = match output_format {
+///     "srg" => Box::new(SrgWriter::new(&mut output)),
+///     _ => Box::new(Tiny2Writer::new(&mut output)),
+/// };
+/// Tiny2Reader::read_str(tiny, &mut writer)?;
+/// drop(writer);
+/// assert_eq!(output, "CL: a com/example/Foo\n");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+impl<V: MappingVisitor + ?Sized> MappingVisitor for Box<V> {
+    fn flags(&self) -> HashSet<MappingFlag> {
+        (**self).flags()
+    }
+
+    fn supports_comments(&self) -> bool {
+        (**self).supports_comments()
+    }
+
+    fn supports_metadata(&self) -> bool {
+        (**self).supports_metadata()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn visit_header(&mut self) -> VisitResult<bool> {
+        (**self).visit_header()
+    }
+
+    fn visit_namespaces(&mut self, src_namespace: &str, dst_namespaces: &[&str]) -> VisitResult<()> {
+        (**self).visit_namespaces(src_namespace, dst_namespaces)
+    }
+
+    fn visit_metadata(&mut self, key: &str, value: &str) -> VisitResult<()> {
+        (**self).visit_metadata(key, value)
+    }
+
+    fn visit_content(&mut self) -> VisitResult<bool> {
+        (**self).visit_content()
+    }
+
+    fn visit_content_size(&mut self, classes: usize) -> VisitResult<()> {
+        (**self).visit_content_size(classes)
+    }
+
+    fn visit_source_position(&mut self, line: usize) -> VisitResult<()> {
+        (**self).visit_source_position(line)
+    }
+
+    fn visit_class(&mut self, src_name: &str) -> VisitResult<bool> {
+        (**self).visit_class(src_name)
+    }
+
+    fn visit_field(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        (**self).visit_field(src_name, src_desc)
+    }
+
+    fn visit_method(&mut self, src_name: &str, src_desc: Option<&str>) -> VisitResult<bool> {
+        (**self).visit_method(src_name, src_desc)
+    }
+
+    fn visit_method_arg(&mut self, arg_position: i32, lv_index: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        (**self).visit_method_arg(arg_position, lv_index, src_name)
+    }
+
+    fn visit_method_var(&mut self, lvt_row_index: i32, lv_index: i32, start_op_idx: i32, src_name: Option<&str>) -> VisitResult<bool> {
+        (**self).visit_method_var(lvt_row_index, lv_index, start_op_idx, src_name)
+    }
+
+    fn visit_end(&mut self) -> VisitResult<bool> {
+        (**self).visit_end()
+    }
+
+    fn visit_dst_name(&mut self, target_kind: MappedElementKind, namespace: usize, name: &str) -> VisitResult<()> {
+        (**self).visit_dst_name(target_kind, namespace, name)
+    }
+
+    fn visit_dst_desc(&mut self, target_kind: MappedElementKind, namespace: usize, desc: &str) -> VisitResult<()> {
+        (**self).visit_dst_desc(target_kind, namespace, desc)
+    }
+
+    fn visit_element_content(&mut self, target_kind: MappedElementKind) -> VisitResult<bool> {
+        (**self).visit_element_content(target_kind)
+    }
+
+    fn visit_comment(&mut self, target_kind: MappedElementKind, comment: &str) -> VisitResult<()> {
+        (**self).visit_comment(target_kind, comment)
+    }
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+//! Tests that a [`MappingTree`] can be shared between threads for concurrent queries.
+
+use std::sync::Arc;
+use std::thread;
+use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+use jvm_obfuscation_mappings::tree::MappingTree;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn mapping_tree_is_send_sync() {
+    assert_send_sync::<MappingTree>();
+}
+
+#[test]
+fn concurrent_queries() -> anyhow::Result<()> {
+    let mut tree = MappingTree::new();
+    Tiny2Reader::read_str(include_str!("fixtures/roundtrip.tiny"), &mut tree)?;
+    let tree = Arc::new(tree);
+    let expected: Vec<(String, Option<String>)> = tree.classes().iter()
+        .map(|class| (class.src_name().to_owned(), class.dst_name(0).map(str::to_owned)))
+        .collect();
+    assert!(!expected.is_empty());
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let tree = Arc::clone(&tree);
+            let expected = expected.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    for (src_name, dst_name) in &expected {
+                        let class = tree.class(src_name).expect("class should exist");
+                        assert_eq!(class.dst_name(0), dst_name.as_deref());
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().expect("query thread panicked");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+//! Tests that every reader rejects zero-byte input with a specific error.
+
+use jvm_obfuscation_mappings::format::enigma::EnigmaSingleFileReader;
+use jvm_obfuscation_mappings::format::proguard::ProGuardReader;
+use jvm_obfuscation_mappings::format::recaf::RecafSimpleReader;
+use jvm_obfuscation_mappings::format::srg::SrgReader;
+use jvm_obfuscation_mappings::format::tiny2::Tiny2Reader;
+use jvm_obfuscation_mappings::tree::MappingTree;
+
+macro_rules! empty_input_test {
+    ($name:ident, $reader:ident, $message:literal) => {
+        #[test]
+        fn $name() {
+            let error = $reader::read(&b""[..], &mut MappingTree::new()).unwrap_err();
+            assert_eq!(error.to_string(), $message);
+        }
+    };
+}
+
+empty_input_test!(enigma_single_file, EnigmaSingleFileReader, "Empty mapping file: missing CLASS entries");
+empty_input_test!(proguard, ProGuardReader, "Empty mapping file: missing class mappings");
+empty_input_test!(recaf_simple, RecafSimpleReader, "Empty mapping file: missing Recaf mappings");
+empty_input_test!(srg, SrgReader, "Empty mapping file: missing SRG entries");
+empty_input_test!(tiny2, Tiny2Reader, "Empty mapping file: missing Tiny v2 header");
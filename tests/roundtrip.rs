@@ -0,0 +1,125 @@
+/*
+ * Copyright (c) 2022 Juuz
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ *
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+//! Round-trip tests for all formats that have both a reader and a writer,
+//! and read tests for the formats that only have a reader.
+//!
+//! Each round-trip test reads a fixture from `tests/fixtures` into a [`MappingTree`], writes it back out,
+//! reads the output again and checks that both trees are equal.
+//! To cover a new format, add a fixture and a `roundtrip_test!` line.
+//! Read-only formats are checked against the same mappings in Tiny v2 with `read_test!` instead.
+
+use jvm_obfuscation_mappings::format::enigma::{EnigmaSingleFileReader, EnigmaSingleFileWriter};
+use jvm_obfuscation_mappings::format::proguard::ProGuardReader;
+use jvm_obfuscation_mappings::format::recaf::RecafSimpleReader;
+use jvm_obfuscation_mappings::format::srg::{SrgReader, SrgWriter};
+use jvm_obfuscation_mappings::format::tiny2::{Tiny2Reader, Tiny2Writer};
+use jvm_obfuscation_mappings::tree::MappingTree;
+
+macro_rules! roundtrip_test {
+    ($name:ident, $fixture:literal, $reader:ident, $writer:ident) => {
+        #[test]
+        fn $name() -> anyhow::Result<()> {
+            let mut original = MappingTree::new();
+            $reader::read_str(include_str!(concat!("fixtures/", $fixture)), &mut original)?;
+            assert!(!original.is_empty(), "Fixture {} is empty", $fixture);
+
+            let mut written = String::new();
+            original.accept(&mut $writer::new(&mut written))?;
+
+            let mut reread = MappingTree::new();
+            $reader::read_str(&written, &mut reread)?;
+
+            assert_eq!(original, reread, "Written mappings:\n{}", written);
+            Ok(())
+        }
+    };
+}
+
+roundtrip_test!(enigma_single_file, "roundtrip.mapping", EnigmaSingleFileReader, EnigmaSingleFileWriter);
+roundtrip_test!(srg, "roundtrip.srg", SrgReader, SrgWriter);
+roundtrip_test!(tiny2, "roundtrip.tiny", Tiny2Reader, Tiny2Writer);
+roundtrip_test!(tiny2_escaped, "roundtrip_escaped.tiny", Tiny2Reader, Tiny2Writer);
+
+macro_rules! read_test {
+    ($name:ident, $fixture:literal, $reader:ident, $expected:expr) => {
+        #[test]
+        fn $name() -> anyhow::Result<()> {
+            let mut read = MappingTree::new();
+            $reader::read_str(include_str!(concat!("fixtures/", $fixture)), &mut read)?;
+
+            let mut expected = MappingTree::new();
+            Tiny2Reader::read_str($expected, &mut expected)?;
+
+            assert_eq!(read, expected);
+            Ok(())
+        }
+    };
+}
+
+read_test!(
+    proguard, "proguard.txt", ProGuardReader,
+    "tiny\t2\t0\tsource\ttarget\n\
+     c\tcom/example/Foo\ta\n\
+     \tf\tI\tcount\ta\n\
+     \tf\tLjava/lang/String;\tname\tb\n\
+     \tm\t(Ljava/lang/String;)V\tsetName\ta\n\
+     \tm\t([I)Lcom/example/Foo;\tcopy\tb\n\
+     c\tcom/example/Bar\tb\n\
+     \tm\t()V\t<init>\t<init>\n"
+);
+read_test!(
+    recaf, "recaf.txt", RecafSimpleReader,
+    "tiny\t2\t0\tsource\ttarget\n\
+     c\ta\tcom/example/Foo\n\
+     \tf\tI\ta\tcount\n\
+     \tm\t(I)V\tb\tsetCount\n\
+     c\tb\tcom/example/Some Class\n"
+);
+
+/// Comments with tabs and line breaks survive a round trip only if they're escaped.
+#[test]
+fn tiny2_escaped_comments() -> anyhow::Result<()> {
+    let mut tree = MappingTree::new();
+    Tiny2Reader::read_str(include_str!("fixtures/roundtrip_escaped.tiny"), &mut tree)?;
+    let class = tree.class("a").unwrap();
+    assert_eq!(class.comment(), Some("First line\nSecond\tcolumn with a \\ backslash"));
+    assert_eq!(class.field("b", Some("I")).unwrap().comment(), Some("Tabbed\tcomment"));
+    Ok(())
+}
+
+/// Streams SRG directly into an [`SrgWriter`] without a tree in between,
+/// so member lines have to carry the mappings of their owners.
+/// Both ways of writing have to reproduce the fixture exactly, without mappings for unmapped owners.
+#[test]
+fn srg_streaming() -> anyhow::Result<()> {
+    let fixture = include_str!("fixtures/roundtrip.srg");
+    let mut original = MappingTree::new();
+    SrgReader::read_str(fixture, &mut original)?;
+
+    let mut from_tree = String::new();
+    original.accept(&mut SrgWriter::new(&mut from_tree))?;
+    assert_eq!(from_tree, fixture);
+
+    let mut written = String::new();
+    SrgReader::read_str(fixture, &mut SrgWriter::new(&mut written))?;
+    assert_eq!(written, fixture);
+
+    let mut reread = MappingTree::new();
+    SrgReader::read_str(&written, &mut reread)?;
+    assert_eq!(original, reread, "Written mappings:\n{}", written);
+    Ok(())
+}